@@ -0,0 +1,3 @@
+-- Must match `PlayerScreenName::MIN_SIZE` and `PlayerScreenName::MAX_SIZE`.
+alter table player
+    add constraint player_screen_name_size check (octet_length(screen_name) between 1 and 30);
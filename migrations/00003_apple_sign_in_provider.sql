@@ -0,0 +1 @@
+alter type third_party_sign_in_provider add value 'Apple';
@@ -0,0 +1,6 @@
+create table email_password_sign_in_method
+(
+    email         text primary key,
+    password_hash text not null,
+    player_id     uuid not null references player (id)
+);
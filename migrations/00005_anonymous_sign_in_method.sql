@@ -0,0 +1,5 @@
+create table anonymous_sign_in_method
+(
+    device_id text primary key,
+    player_id uuid not null references player (id)
+);
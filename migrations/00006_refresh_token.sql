@@ -0,0 +1,11 @@
+create table refresh_token
+(
+    token_hash bytea primary key,
+    family_id  uuid        not null,
+    player_id  uuid        not null references player (id),
+    expires_at timestamptz not null,
+    rotated    boolean     not null default false,
+    revoked    boolean     not null default false
+);
+
+create index refresh_token_family_id_idx on refresh_token (family_id);
@@ -0,0 +1,7 @@
+create table revoked_token
+(
+    jti        uuid primary key,
+    expires_at timestamptz not null
+);
+
+create index revoked_token_expires_at_idx on revoked_token (expires_at);
@@ -0,0 +1,13 @@
+create type notification_kind as enum ('MatchFound', 'GameResumed', 'Challenged');
+
+create table notification
+(
+    id         uuid primary key,
+    player_id  uuid              not null references player (id),
+    kind       notification_kind not null,
+    message    text              not null,
+    created_at timestamptz       not null,
+    read       boolean           not null default false
+);
+
+create index notification_player_id_id_idx on notification (player_id, id desc);
@@ -0,0 +1,4 @@
+-- Must match `PlayerScreenName::MIN_SIZE` and `PlayerScreenName::MAX_SIZE`, counted in characters.
+alter table player
+    drop constraint player_screen_name_size,
+    add constraint player_screen_name_size check (char_length(screen_name) between 1 and 30);
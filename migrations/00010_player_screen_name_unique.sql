@@ -0,0 +1,10 @@
+-- Renames all but the earliest joined player of case-insensitively equal screen names, keeping
+-- them within `PlayerScreenName::MAX_SIZE` characters.
+update player p
+set screen_name = left(p.screen_name, 21) || '-' || left(md5(p.id::text), 8)
+where exists(select 1
+             from player other
+             where lower(other.screen_name) = lower(p.screen_name)
+               and (other.joined_at, other.id) < (p.joined_at, p.id));
+
+create unique index player_screen_name_unique_idx on player (lower(screen_name));
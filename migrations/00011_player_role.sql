@@ -0,0 +1,4 @@
+create type player_role as enum ('Player', 'Admin');
+
+alter table player
+    add column role player_role not null default 'Player';
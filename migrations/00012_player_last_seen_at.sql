@@ -0,0 +1,2 @@
+alter table player
+    add column last_seen_at timestamptz;
@@ -0,0 +1,2 @@
+alter table player
+    add column preferences jsonb;
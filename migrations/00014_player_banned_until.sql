@@ -0,0 +1,2 @@
+alter table player
+    add column banned_until timestamptz;
@@ -0,0 +1,16 @@
+create type friendship_state as enum ('Pending', 'Accepted', 'Blocked');
+
+create table friendship
+(
+    requester_id uuid             not null references player (id),
+    addressee_id uuid             not null references player (id),
+    state        friendship_state not null,
+    created_at   timestamptz      not null default now(),
+    primary key (requester_id, addressee_id),
+    check (requester_id <> addressee_id)
+);
+
+create unique index friendship_pair_idx
+    on friendship (least(requester_id, addressee_id), greatest(requester_id, addressee_id));
+
+create index friendship_addressee_id_idx on friendship (addressee_id);
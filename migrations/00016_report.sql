@@ -0,0 +1,15 @@
+create type report_status as enum ('Open', 'Resolved');
+
+create table report
+(
+    id          uuid primary key,
+    reporter_id uuid          not null references player (id),
+    reported_id uuid          not null references player (id),
+    reason      text          not null,
+    created_at  timestamptz   not null default now(),
+    status      report_status not null default 'Open'
+);
+
+create index report_reporter_id_created_at_idx on report (reporter_id, created_at);
+
+create index report_open_idx on report (id) where status = 'Open';
@@ -0,0 +1,3 @@
+alter table anonymous_sign_in_method
+    drop constraint anonymous_sign_in_method_pkey,
+    add primary key (player_id);
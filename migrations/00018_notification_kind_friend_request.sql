@@ -0,0 +1,3 @@
+alter type notification_kind add value 'FriendRequested';
+
+alter type notification_kind add value 'FriendRequestAccepted';
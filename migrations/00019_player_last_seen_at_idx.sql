@@ -0,0 +1 @@
+create index player_last_seen_at_idx on player (last_seen_at);
@@ -0,0 +1,6 @@
+-- Reports are kept for moderation when players delete their accounts: reporters are anonymised
+-- and reported players may no longer exist.
+alter table report
+    alter column reporter_id drop not null,
+    drop constraint report_reported_id_fkey,
+    add column game_id uuid;
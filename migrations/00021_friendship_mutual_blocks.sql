@@ -0,0 +1,6 @@
+-- Players may block each other, so there can be a block in each direction of a pair.
+drop index friendship_pair_idx;
+
+create unique index friendship_pair_idx
+    on friendship (least(requester_id, addressee_id), greatest(requester_id, addressee_id))
+    where state <> 'Blocked';
@@ -1,3 +1,10 @@
+use crate::config::ApiErrorConfig;
+use axum::extract::Request;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
@@ -12,4 +19,220 @@ pub struct ApiError<'a> {
 
     /// Error message for the developer.
     pub dev_message: Cow<'a, str>,
+
+    /// ID of the request that has failed, same as in the `x-request-id` response header and in
+    /// the logs. Empty if the request has no ID.
+    #[serde(default)]
+    pub trace_id: Cow<'a, str>,
+}
+
+impl ApiError<'static> {
+    /// Creates a new [ApiError] of the given [ErrorCode] without a [ApiError::trace_id].
+    pub fn new(code: ErrorCode, dev_message: impl Into<Cow<'static, str>>) -> ApiError<'static> {
+        ApiError {
+            module: code.module.into(),
+            id: code.id,
+            dev_message: dev_message.into(),
+            trace_id: "".into(),
+        }
+    }
+
+    /// Returns a JSON [Response] with the given [StatusCode] and this [ApiError]. The [ApiError]
+    /// is also kept in the response extensions, so that [crate::logging] can add the
+    /// [ApiError::trace_id] to it.
+    pub fn into_response(self, status: StatusCode) -> Response {
+        let mut response = (status, Json(&self)).into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Typed code of an [ApiError], i.e. its [ApiError::module] and [ApiError::id]. Every module
+/// declares its codes as named constants, the ids are a part of the API contract and must never
+/// be changed or reused.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ErrorCode {
+    /// [ApiError::module] of the code.
+    pub module: &'static str,
+
+    /// [ApiError::id] of the code.
+    pub id: u32,
+}
+
+impl ErrorCode {
+    /// Creates a new [ErrorCode].
+    pub const fn new(module: &'static str, id: u32) -> ErrorCode {
+        ErrorCode { module, id }
+    }
+
+    /// Whether the ids of the given [ErrorCode]s of a module are unique. Meant to be checked at
+    /// compile time with `const _: () = assert!(ErrorCode::ids_unique(..));`.
+    pub const fn ids_unique(codes: &[ErrorCode]) -> bool {
+        let mut i = 0;
+        while i < codes.len() {
+            let mut j = i + 1;
+            while j < codes.len() {
+                if codes[i].id == codes[j].id {
+                    return false;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+
+        true
+    }
+}
+
+/// RFC 7807 problem details representation of an [ApiError].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemDetails<'a> {
+    /// URI identifying the problem type, built from [ApiError::module] and [ApiError::id].
+    #[serde(rename = "type")]
+    pub problem_type: String,
+
+    /// Reason phrase of [ProblemDetails::status].
+    pub title: Cow<'a, str>,
+
+    /// HTTP status code of the response.
+    pub status: u16,
+
+    /// [ApiError::dev_message].
+    pub detail: Cow<'a, str>,
+
+    /// Path of the failed request.
+    pub instance: Cow<'a, str>,
+
+    /// [ApiError::module].
+    pub module: Cow<'a, str>,
+
+    /// [ApiError::id].
+    pub id: u32,
+
+    /// [ApiError::trace_id].
+    #[serde(default)]
+    pub trace_id: Cow<'a, str>,
+}
+
+impl<'a> ProblemDetails<'a> {
+    /// Creates [ProblemDetails] of the given [ApiError] returned with the given [StatusCode] for
+    /// the given request path.
+    pub fn new(
+        api_error: ApiError<'a>,
+        status: StatusCode,
+        instance: String,
+    ) -> ProblemDetails<'a> {
+        ProblemDetails {
+            problem_type: format!("urn:gamehub:error:{}:{}", api_error.module, api_error.id),
+            title: status.canonical_reason().unwrap_or_default().into(),
+            status: status.as_u16(),
+            detail: api_error.dev_message,
+            instance: instance.into(),
+            module: api_error.module,
+            id: api_error.id,
+            trace_id: api_error.trace_id,
+        }
+    }
+}
+
+impl IntoResponse for ProblemDetails<'_> {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(self)).into_response();
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+        response
+    }
+}
+
+/// Wraps the given [Router] so that [ApiError] responses are returned in the format of the given
+/// [ApiErrorConfig]. Must wrap [crate::logging::with_request_id] to keep [ApiError::trace_id].
+pub fn with_format(router: Router, config: &ApiErrorConfig) -> Router {
+    if config.problem_json {
+        router.layer(axum::middleware::from_fn(render_problem_json))
+    } else {
+        router
+    }
+}
+
+/// Renders [ApiError] responses as [ProblemDetails], keeping the headers of the response.
+async fn render_problem_json(request: Request, next: Next) -> Response {
+    let instance = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+    let Some(api_error) = response.extensions().get::<ApiError>().cloned() else {
+        return response;
+    };
+
+    let (mut parts, _) = response.into_parts();
+    let problem_response = ProblemDetails::new(api_error, parts.status, instance).into_response();
+    parts.headers.extend(problem_response.headers().clone());
+
+    Response::from_parts(parts, problem_response.into_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use axum_test::TestServer;
+
+    #[test]
+    fn api_error_json_snapshot() {
+        insta::assert_json_snapshot!(&ApiError {
+            module: "players".into(),
+            id: 1,
+            dev_message: "player not found".into(),
+            trace_id: "0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b".into(),
+        });
+    }
+
+    #[test]
+    fn error_code_ids_unique() {
+        let a = ErrorCode::new("test", 0);
+        let b = ErrorCode::new("test", 1);
+
+        assert!(ErrorCode::ids_unique(&[]));
+        assert!(ErrorCode::ids_unique(&[a, b]));
+        assert!(!ErrorCode::ids_unique(&[a, b, a]));
+    }
+
+    async fn failure() -> Response {
+        ApiError::new(ErrorCode::new("test", 1), "test error").into_response(StatusCode::CONFLICT)
+    }
+
+    #[tokio::test]
+    async fn with_format_renders_problem_json_if_enabled() -> anyhow::Result<()> {
+        let router = Router::new().route("/failure", get(failure));
+        let server = TestServer::new(with_format(router, &ApiErrorConfig { problem_json: true }))?;
+
+        let response = server.get("/failure").await;
+
+        response.assert_status(StatusCode::CONFLICT);
+        assert_eq!(response.header(CONTENT_TYPE), "application/problem+json");
+        let problem = response.json::<ProblemDetails>();
+        assert_eq!(problem.problem_type, "urn:gamehub:error:test:1");
+        assert_eq!(problem.title, "Conflict");
+        assert_eq!(problem.status, 409);
+        assert_eq!(problem.detail, "test error");
+        assert_eq!(problem.instance, "/failure");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_format_keeps_api_error_if_disabled() -> anyhow::Result<()> {
+        let router = Router::new().route("/failure", get(failure));
+        let server = TestServer::new(with_format(router, &ApiErrorConfig::default()))?;
+
+        let response = server.get("/failure").await;
+
+        response.assert_status(StatusCode::CONFLICT);
+        assert_eq!(response.header(CONTENT_TYPE), "application/json");
+        assert_eq!(response.json::<ApiError>().id, 1);
+
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
 use crate::players::jwt_service::{JwtService, JwtServiceDefault};
 use crate::players::players_service::{PlayersService, PlayersServiceDefault};
+use crate::players::presence::{PresenceService, PresenceServiceDefault};
+use crate::players::sign_in_rate_limiter::SignInRateLimiter;
 use std::sync::Arc;
 
 /// Application state, shared between HTTP handlers.
@@ -13,40 +15,67 @@ pub trait AppState: Clone + Send + Sync + 'static {
     type JwtService: JwtService + Send + Sync + 'static;
     /// Returns a reference to [Self::JwtService] implementation.
     fn jwt_service(&self) -> &Self::JwtService;
+
+    /// [PresenceService] implementation.
+    type PresenceService: PresenceService + Send + Sync + 'static;
+    /// Returns a reference to [Self::PresenceService] implementation.
+    fn presence_service(&self) -> &Self::PresenceService;
+
+    /// Returns the [SignInRateLimiter], if sign-ins are rate limited.
+    fn sign_in_rate_limiter(&self) -> Option<&SignInRateLimiter>;
 }
 
 /// Default [AppState] implementation.
 #[derive(Default)]
 #[cfg_attr(test, derive(getset::WithSetters))]
 #[cfg_attr(test, getset(set_with = "pub"))]
-pub struct AppStateDefault<PS = PlayersServiceDefault, JS = JwtServiceDefault> {
+pub struct AppStateDefault<
+    PS = PlayersServiceDefault,
+    JS = JwtServiceDefault,
+    PrS = PresenceServiceDefault,
+> {
     /// [PlayersService] implementation.
     players_service: PS,
 
     /// [JwtService] implementation.
     jwt_service: JS,
+
+    /// [PresenceService] implementation.
+    presence_service: PrS,
+
+    /// [SignInRateLimiter], if sign-ins are rate limited.
+    sign_in_rate_limiter: Option<SignInRateLimiter>,
 }
 
 #[cfg(test)]
 pub type MockAppState = AppStateDefault<
     crate::players::players_service::MockPlayersService,
     crate::players::jwt_service::MockJwtService,
+    crate::players::presence::MockPresenceService,
 >;
 
-impl<PS, JS> AppStateDefault<PS, JS> {
+impl<PS, JS, PrS> AppStateDefault<PS, JS, PrS> {
     /// Creates a new [AppStateDefault] with the given services.
-    pub fn new(players_service: PS, jwt_service: JS) -> AppStateDefault<PS, JS> {
+    pub fn new(
+        players_service: PS,
+        jwt_service: JS,
+        presence_service: PrS,
+        sign_in_rate_limiter: Option<SignInRateLimiter>,
+    ) -> AppStateDefault<PS, JS, PrS> {
         AppStateDefault {
             players_service,
             jwt_service,
+            presence_service,
+            sign_in_rate_limiter,
         }
     }
 }
 
-impl<PS, JS> AppState for Arc<AppStateDefault<PS, JS>>
+impl<PS, JS, PrS> AppState for Arc<AppStateDefault<PS, JS, PrS>>
 where
     PS: PlayersService + Send + Sync + 'static,
     JS: JwtService + Send + Sync + 'static,
+    PrS: PresenceService + Send + Sync + 'static,
 {
     type PlayersService = PS;
     fn players_service(&self) -> &Self::PlayersService {
@@ -57,4 +86,13 @@ where
     fn jwt_service(&self) -> &Self::JwtService {
         &self.jwt_service
     }
+
+    type PresenceService = PrS;
+    fn presence_service(&self) -> &Self::PresenceService {
+        &self.presence_service
+    }
+
+    fn sign_in_rate_limiter(&self) -> Option<&SignInRateLimiter> {
+        self.sign_in_rate_limiter.as_ref()
+    }
 }
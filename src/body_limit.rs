@@ -0,0 +1,128 @@
+use crate::api_error::{ApiError, ErrorCode};
+use axum::Router;
+use axum::extract::{DefaultBodyLimit, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// [ErrorCode] of rejected too large request bodies.
+pub const PAYLOAD_TOO_LARGE: ErrorCode = ErrorCode::new("body_limit", 0);
+
+/// Wraps the given [Router] so that request bodies larger than `max_body_size` bytes are
+/// rejected with `413 Payload Too Large` as an [ApiError].
+///
+/// Routes needing a different limit, e.g. uploads, can override it with their own
+/// [DefaultBodyLimit] layer, the innermost one is applied.
+pub fn with_body_limit<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,
+    max_body_size: usize,
+) -> Router<S> {
+    router
+        .layer(DefaultBodyLimit::max(max_body_size))
+        .layer(axum::middleware::from_fn_with_state(
+            max_body_size,
+            reject_payload_too_large,
+        ))
+}
+
+/// Replaces the plain text `413 Payload Too Large` rejections of the extractors with an
+/// [ApiError].
+async fn reject_payload_too_large(
+    State(max_body_size): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE
+        || response.extensions().get::<ApiError>().is_some()
+    {
+        return response;
+    }
+
+    ApiError::new(
+        PAYLOAD_TOO_LARGE,
+        format!("request body is larger than {max_body_size} bytes"),
+    )
+    .into_response(StatusCode::PAYLOAD_TOO_LARGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Json;
+    use axum::routing::post;
+    use axum_test::TestServer;
+    use serde::Deserialize;
+
+    const MAX_BODY_SIZE: usize = 64;
+
+    #[derive(Deserialize)]
+    struct Body {
+        #[allow(dead_code, reason = "only the size of the body matters")]
+        value: String,
+    }
+
+    async fn echo(Json(_): Json<Body>) -> StatusCode {
+        StatusCode::OK
+    }
+
+    fn server() -> anyhow::Result<TestServer> {
+        let router = Router::new().route("/echo", post(echo)).route(
+            "/upload",
+            post(echo).layer(DefaultBodyLimit::max(MAX_BODY_SIZE * 4)),
+        );
+
+        TestServer::new(with_body_limit(router, MAX_BODY_SIZE))
+    }
+
+    fn body_of_size(size: usize) -> String {
+        format!(r#"{{"value":"{}"}}"#, "v".repeat(size - 12))
+    }
+
+    #[tokio::test]
+    async fn with_body_limit_accepts_body_within_limit() -> anyhow::Result<()> {
+        let server = server()?;
+
+        server
+            .post("/echo")
+            .text(body_of_size(MAX_BODY_SIZE))
+            .content_type("application/json")
+            .await
+            .assert_status(StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_body_limit_rejects_oversized_body_with_api_error() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server
+            .post("/echo")
+            .text(body_of_size(MAX_BODY_SIZE + 1))
+            .content_type("application/json")
+            .await;
+
+        response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, PAYLOAD_TOO_LARGE.module);
+        assert_eq!(error.id, PAYLOAD_TOO_LARGE.id);
+        assert_eq!(error.dev_message, "request body is larger than 64 bytes");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_body_limit_can_be_overridden_per_route() -> anyhow::Result<()> {
+        let server = server()?;
+
+        server
+            .post("/upload")
+            .text(body_of_size(MAX_BODY_SIZE * 2))
+            .content_type("application/json")
+            .await
+            .assert_status(StatusCode::OK);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::api_error::{ApiError, ErrorCode};
+use axum::Router;
+use axum::http::StatusCode;
+use axum::response::Response;
+use std::any::Any;
+use tower_http::catch_panic::CatchPanicLayer;
+use tracing::error;
+
+/// [ErrorCode] of requests whose handler has panicked.
+pub const INTERNAL: ErrorCode = ErrorCode::new("internal", 0);
+
+/// Wraps the given [Router] so that panics of its handlers are logged and returned as a
+/// `500 Internal Server Error` [ApiError] instead of dropping the connection. Must be wrapped by
+/// [crate::logging::with_request_id] to log the panic with the request ID.
+pub fn with_catch_panic(router: Router) -> Router {
+    router.layer(CatchPanicLayer::custom(handle_panic))
+}
+
+/// Logs the panic and returns the [INTERNAL] [ApiError], the panic message is not exposed.
+fn handle_panic(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    error!("Handler panicked: {message}");
+
+    ApiError::new(INTERNAL, "internal server error")
+        .into_response(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use axum_test::TestServer;
+
+    async fn panicking() -> StatusCode {
+        panic!("secret panic message")
+    }
+
+    #[tokio::test]
+    async fn with_catch_panic_returns_api_error_with_request_id() -> anyhow::Result<()> {
+        let router = Router::new().route("/panic", get(panicking));
+        let server = TestServer::new(crate::logging::with_request_id(with_catch_panic(router)))?;
+
+        let response = server.get("/panic").await;
+
+        response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, INTERNAL.module);
+        assert_eq!(error.id, INTERNAL.id);
+        assert_eq!(error.trace_id, response.header("x-request-id").to_str()?);
+        assert!(!response.text().contains("secret panic message"));
+
+        Ok(())
+    }
+}
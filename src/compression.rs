@@ -0,0 +1,116 @@
+use crate::config::{CompressionAlgorithm, CompressionConfig};
+use axum::Router;
+use tower_http::compression::CompressionLayer;
+use tower_http::compression::predicate::{DefaultPredicate, Predicate, SizeAbove};
+
+/// Wraps the given [Router] so that responses of at least [CompressionConfig::min_size] bytes
+/// are compressed with one of the [CompressionConfig::algorithms] accepted by the client.
+pub fn with_compression(router: Router, config: &CompressionConfig) -> Router {
+    if !config.enabled {
+        return router;
+    }
+
+    let enabled = |algorithm| config.algorithms.contains(&algorithm);
+    router.layer(
+        CompressionLayer::new()
+            .gzip(enabled(CompressionAlgorithm::Gzip))
+            .br(enabled(CompressionAlgorithm::Br))
+            .compress_when(DefaultPredicate::new().and(SizeAbove::new(config.min_size))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Json;
+    use axum::http::StatusCode;
+    use axum::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, VARY};
+    use axum::routing::get;
+    use axum_test::TestServer;
+    use std::io::Read;
+
+    const MIN_SIZE: u16 = 1024;
+
+    fn server(algorithms: Vec<CompressionAlgorithm>) -> anyhow::Result<TestServer> {
+        let router = Router::new()
+            .route("/health", get(|| async { StatusCode::OK }))
+            .route("/small", get(|| async { Json(vec!["small"]) }))
+            .route(
+                "/large",
+                get(|| async { Json(vec!["player".to_string(); MIN_SIZE.into()]) }),
+            );
+
+        TestServer::new(with_compression(
+            router,
+            &CompressionConfig {
+                enabled: true,
+                min_size: MIN_SIZE,
+                algorithms,
+            },
+        ))
+    }
+
+    #[tokio::test]
+    async fn with_compression_gzips_large_responses() -> anyhow::Result<()> {
+        let server = server(vec![CompressionAlgorithm::Gzip])?;
+
+        let plain = server.get("/large").await;
+        let compressed = server
+            .get("/large")
+            .add_header(ACCEPT_ENCODING, "br;q=1.0, gzip;q=0.8")
+            .await;
+
+        assert!(plain.maybe_header(CONTENT_ENCODING).is_none());
+        assert_eq!(compressed.header(CONTENT_ENCODING), "gzip");
+        assert_eq!(compressed.header(VARY), "accept-encoding");
+        let gzipped = compressed.as_bytes();
+        assert!(gzipped.len() < plain.as_bytes().len());
+        let mut decompressed = vec![];
+        flate2::read::GzDecoder::new(gzipped.as_ref()).read_to_end(&mut decompressed)?;
+        assert_eq!(decompressed, plain.as_bytes().as_ref());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_compression_prefers_the_algorithm_preferred_by_the_client() -> anyhow::Result<()>
+    {
+        let server = server(vec![CompressionAlgorithm::Gzip, CompressionAlgorithm::Br])?;
+
+        let response = server
+            .get("/large")
+            .add_header(ACCEPT_ENCODING, "br;q=1.0, gzip;q=0.8")
+            .await;
+
+        assert_eq!(response.header(CONTENT_ENCODING), "br");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_compression_skips_small_responses_and_clients_not_accepting_algorithms()
+    -> anyhow::Result<()> {
+        let server = server(vec![CompressionAlgorithm::Gzip])?;
+
+        for (path, accept_encoding) in [
+            ("/health", "gzip"),
+            ("/small", "gzip"),
+            ("/large", "br"),
+            ("/large", "gzip;q=0"),
+            ("/large", "gzip;q=0, *"),
+        ] {
+            let response = server
+                .get(path)
+                .add_header(ACCEPT_ENCODING, accept_encoding)
+                .await;
+
+            response.assert_status(StatusCode::OK);
+            assert!(
+                response.maybe_header(CONTENT_ENCODING).is_none(),
+                "{path} {accept_encoding}"
+            );
+        }
+
+        Ok(())
+    }
+}
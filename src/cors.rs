@@ -0,0 +1,149 @@
+use crate::config::CorsConfig;
+use anyhow::Context;
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Builds a [CorsLayer] from the given [CorsConfig].
+///
+/// Only the listed origins are allowed, a wildcard is never used as browsers reject it for
+/// requests with credentials.
+pub fn layer(config: &CorsConfig) -> anyhow::Result<CorsLayer> {
+    let allowed_origins = config
+        .allowed_origins
+        .iter()
+        .map(|origin| {
+            HeaderValue::from_str(origin).with_context(|| format!("invalid CORS origin {origin}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let layer = CorsLayer::new()
+        .allow_origin(AllowOrigin::list(allowed_origins))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([AUTHORIZATION, CONTENT_TYPE])
+        .expose_headers([HeaderName::from_static("x-request-id")])
+        .allow_credentials(config.allow_credentials)
+        .max_age(config.max_age);
+
+    Ok(layer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::http::header::{
+        ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_METHODS,
+        ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+    };
+    use axum::routing::{post, put};
+    use axum_test::TestServer;
+    use std::time::Duration;
+
+    const ALLOWED_ORIGIN: &str = "https://gamehub.test";
+
+    fn server() -> anyhow::Result<TestServer> {
+        let config = CorsConfig {
+            allowed_origins: vec![ALLOWED_ORIGIN.to_string()],
+            allow_credentials: true,
+            max_age: Duration::from_secs(600),
+        };
+        let router = Router::new()
+            .route("/players/sign_in", post(|| async { StatusCode::OK }))
+            .route("/players/me/preferences", put(|| async { StatusCode::OK }))
+            .route("/players/{id}/ban", put(|| async { StatusCode::OK }))
+            .layer(layer(&config)?);
+
+        TestServer::new(router)
+    }
+
+    #[tokio::test]
+    async fn layer_allows_preflight_of_listed_origin() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server
+            .method(Method::OPTIONS, "/players/sign_in")
+            .add_header(ORIGIN, ALLOWED_ORIGIN)
+            .add_header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        assert_eq!(response.header(ACCESS_CONTROL_ALLOW_ORIGIN), ALLOWED_ORIGIN);
+        assert_eq!(response.header(ACCESS_CONTROL_ALLOW_CREDENTIALS), "true");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn layer_allows_preflight_of_preferences_update() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server
+            .method(Method::OPTIONS, "/players/me/preferences")
+            .add_header(ORIGIN, ALLOWED_ORIGIN)
+            .add_header(ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        assert_eq!(response.header(ACCESS_CONTROL_ALLOW_ORIGIN), ALLOWED_ORIGIN);
+        assert!(
+            response
+                .header(ACCESS_CONTROL_ALLOW_METHODS)
+                .to_str()?
+                .contains("PUT")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn layer_allows_preflight_of_ban() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server
+            .method(
+                Method::OPTIONS,
+                "/players/00000000-0000-0000-0000-0000499602d2/ban",
+            )
+            .add_header(ORIGIN, ALLOWED_ORIGIN)
+            .add_header(ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        assert_eq!(response.header(ACCESS_CONTROL_ALLOW_ORIGIN), ALLOWED_ORIGIN);
+        assert!(
+            response
+                .header(ACCESS_CONTROL_ALLOW_METHODS)
+                .to_str()?
+                .contains("PUT")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn layer_rejects_preflight_of_unlisted_origin() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server
+            .method(Method::OPTIONS, "/players/sign_in")
+            .add_header(ORIGIN, "https://evil.test")
+            .add_header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .await;
+
+        assert!(response.maybe_header(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn layer_fails_if_origin_is_invalid() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://gamehub.test\n".to_string()],
+            ..CorsConfig::default()
+        };
+
+        assert!(layer(&config).is_err());
+    }
+}
@@ -1,25 +1,131 @@
+use axum::Json;
+use axum::extract::State;
 use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::warn;
 
-/// Healthcheck router.
-pub fn router() -> axum::Router {
-    axum::Router::new().route("/health", axum::routing::get(health))
+/// Healthcheck router. `/health/ready` checks the given [PgPool].
+pub fn router(pg_pool: PgPool) -> axum::Router {
+    axum::Router::new()
+        .route("/health", axum::routing::get(health))
+        .route("/health/live", axum::routing::get(health))
+        .route("/health/ready", axum::routing::get(ready))
+        .route("/version", axum::routing::get(version))
+        .with_state(pg_pool)
 }
 
-/// Healthcheck handler.
+/// Build metadata of the running app, captured at compile time by `build.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Version {
+    /// Crate version.
+    pub version: String,
+
+    /// Git commit SHA the app was built from, `unknown` if it was built outside of git.
+    pub git_sha: String,
+
+    /// When the app was built.
+    pub built_at: Option<DateTime<Utc>>,
+}
+
+/// Healthcheck and liveness handler, the process is up if it responds.
 pub async fn health() -> StatusCode {
     StatusCode::OK
 }
 
+/// Readiness handler, the app is ready if Postgres is reachable.
+pub async fn ready(State(pg_pool): State<PgPool>) -> StatusCode {
+    match sqlx::query("select 1").execute(&pg_pool).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => {
+            warn!("Readiness check failed: {e}");
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// Version handler, returns the [Version] of the running app.
+pub async fn version() -> Json<Version> {
+    Json(Version {
+        version: env!("CARGO_PKG_VERSION").into(),
+        git_sha: env!("GAMEHUB_GIT_SHA").into(),
+        built_at: env!("GAMEHUB_BUILD_TIMESTAMP")
+            .parse()
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use axum_test::TestServer;
+    use sqlx::postgres::PgPoolOptions;
+    use std::time::Duration;
+
+    fn unreachable_pg_pool() -> anyhow::Result<PgPool> {
+        let pg_pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("postgres://postgres@127.0.0.1:1/gamehub")?;
+
+        Ok(pg_pool)
+    }
 
     #[tokio::test]
     async fn test_health() -> anyhow::Result<()> {
-        let router = TestServer::new(router())?;
+        let router = TestServer::new(router(unreachable_pg_pool()?))?;
 
         router.get("/health").await.assert_status(StatusCode::OK);
+        router
+            .get("/health/live")
+            .await
+            .assert_status(StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_version() -> anyhow::Result<()> {
+        let router = TestServer::new(router(unreachable_pg_pool()?))?;
+
+        let response = router.get("/version").await;
+
+        response.assert_status(StatusCode::OK);
+        let version = response.json::<Version>();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        assert!(!version.git_sha.is_empty());
+        assert!(version.built_at.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ready_fails_if_postgres_is_unreachable() -> anyhow::Result<()> {
+        let closed_pg_pool = unreachable_pg_pool()?;
+        closed_pg_pool.close().await;
+
+        for pg_pool in [unreachable_pg_pool()?, closed_pg_pool] {
+            let router = TestServer::new(router(pg_pool))?;
+
+            router
+                .get("/health/ready")
+                .await
+                .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn test_ready(pg_pool: PgPool) -> anyhow::Result<()> {
+        let router = TestServer::new(router(pg_pool))?;
+
+        router
+            .get("/health/ready")
+            .await
+            .assert_status(StatusCode::OK);
 
         Ok(())
     }
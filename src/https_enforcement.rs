@@ -0,0 +1,257 @@
+use crate::api_error::{ApiError, ErrorCode};
+use crate::config::{HttpsEnforcementConfig, HttpsEnforcementMode};
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::header::LOCATION;
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::net::SocketAddr;
+
+/// Header set by TLS terminating proxies with the protocol of the original request.
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+
+/// [ErrorCode] of rejected insecure requests.
+pub const INSECURE_REQUEST: ErrorCode = ErrorCode::new("https", 0);
+
+/// [Request] extension of requests received over TLS terminated by the server itself.
+#[derive(Debug, Clone, Copy)]
+pub struct TerminatedTls;
+
+/// Middleware enforcing HTTPS according to the given [HttpsEnforcementConfig].
+///
+/// A request is considered secure if it has the [TerminatedTls] extension, or if it comes from
+/// one of the trusted proxies with `X-Forwarded-Proto: https`.
+pub async fn enforce_https(
+    State(config): State<HttpsEnforcementConfig>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if config.mode == HttpsEnforcementMode::Disabled || is_secure(&config, &peer, &request) {
+        return next.run(request).await;
+    }
+
+    match (config.mode, https_location(&config, &request)) {
+        (HttpsEnforcementMode::Redirect, Some(location)) => {
+            (StatusCode::MOVED_PERMANENTLY, [(LOCATION, location)]).into_response()
+        }
+        _ => ApiError::new(INSECURE_REQUEST, "insecure request, use https")
+            .into_response(StatusCode::BAD_REQUEST),
+    }
+}
+
+/// Whether the given [Request] was sent over HTTPS to the server or a trusted proxy. The last
+/// `X-Forwarded-Proto` value is used, as the ones before it come from the client.
+fn is_secure(config: &HttpsEnforcementConfig, peer: &SocketAddr, request: &Request) -> bool {
+    if request.extensions().get::<TerminatedTls>().is_some() {
+        return true;
+    }
+    if !config.trusted_proxies.contains(&peer.ip()) {
+        return false;
+    }
+
+    request
+        .headers()
+        .get(X_FORWARDED_PROTO)
+        .and_then(|header_value| header_value.to_str().ok())
+        .and_then(|protos| protos.rsplit(',').next())
+        .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"))
+}
+
+/// Returns the HTTPS location of the given [Request] on the
+/// [HttpsEnforcementConfig::canonical_host], if one is configured.
+fn https_location(config: &HttpsEnforcementConfig, request: &Request) -> Option<HeaderValue> {
+    let host = config.canonical_host.as_deref()?;
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or("/");
+
+    HeaderValue::from_str(&format!("https://{host}{path_and_query}")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::extract::connect_info::MockConnectInfo;
+    use axum::http::header::HOST;
+    use axum::routing::get;
+    use axum_test::TestServer;
+
+    const TRUSTED_PROXY: [u8; 4] = [10, 0, 0, 1];
+    const UNTRUSTED_PEER: [u8; 4] = [10, 0, 0, 2];
+
+    fn server(mode: HttpsEnforcementMode, peer: [u8; 4]) -> anyhow::Result<TestServer> {
+        let config = HttpsEnforcementConfig {
+            mode,
+            trusted_proxies: vec![TRUSTED_PROXY.into()],
+            canonical_host: Some("gamehub.test".to_string()),
+        };
+
+        let router = Router::new()
+            .route("/ping", get(|| async { "pong" }))
+            .layer(axum::middleware::from_fn_with_state(config, enforce_https))
+            .layer(MockConnectInfo(SocketAddr::from((peer, 1234))));
+
+        let mut server = TestServer::new(router)?;
+        server.add_header(HOST, "gamehub.test");
+
+        Ok(server)
+    }
+
+    #[tokio::test]
+    async fn enforce_https_redirects_insecure_requests_in_redirect_mode() -> anyhow::Result<()> {
+        let server = server(HttpsEnforcementMode::Redirect, TRUSTED_PROXY)?;
+
+        let response = server
+            .get("/ping?a=1")
+            .add_header(X_FORWARDED_PROTO, "http")
+            .await;
+
+        response.assert_status(StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(response.header(LOCATION), "https://gamehub.test/ping?a=1");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_redirects_to_canonical_host_regardless_of_host_header()
+    -> anyhow::Result<()> {
+        let server = server(HttpsEnforcementMode::Redirect, TRUSTED_PROXY)?;
+
+        let response = server
+            .get("/ping")
+            .add_header(HOST, "evil.test")
+            .add_header(X_FORWARDED_PROTO, "http")
+            .await;
+
+        response.assert_status(StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(response.header(LOCATION), "https://gamehub.test/ping");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_rejects_insecure_requests_without_canonical_host() -> anyhow::Result<()>
+    {
+        let config = HttpsEnforcementConfig {
+            mode: HttpsEnforcementMode::Redirect,
+            trusted_proxies: vec![],
+            canonical_host: None,
+        };
+        let router = Router::new()
+            .route("/ping", get(|| async { "pong" }))
+            .layer(axum::middleware::from_fn_with_state(config, enforce_https))
+            .layer(MockConnectInfo(SocketAddr::from((UNTRUSTED_PEER, 1234))));
+        let server = TestServer::new(router)?;
+
+        let response = server.get("/ping").add_header(HOST, "evil.test").await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert!(response.maybe_header(LOCATION).is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_rejects_insecure_requests_in_reject_mode() -> anyhow::Result<()> {
+        let server = server(HttpsEnforcementMode::Reject, TRUSTED_PROXY)?;
+
+        let response = server
+            .get("/ping")
+            .add_header(X_FORWARDED_PROTO, "http")
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, "https");
+        assert_eq!(error.id, 0);
+        assert_eq!(error.trace_id, "");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_passes_secure_requests() -> anyhow::Result<()> {
+        for mode in [HttpsEnforcementMode::Redirect, HttpsEnforcementMode::Reject] {
+            let server = server(mode, TRUSTED_PROXY)?;
+
+            let response = server
+                .get("/ping")
+                .add_header(X_FORWARDED_PROTO, "https")
+                .await;
+
+            response.assert_status(StatusCode::OK);
+            response.assert_text("pong");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_uses_forwarded_proto_of_last_hop() -> anyhow::Result<()> {
+        let server = server(HttpsEnforcementMode::Reject, TRUSTED_PROXY)?;
+
+        let spoofed = server
+            .get("/ping")
+            .add_header(X_FORWARDED_PROTO, "https, http")
+            .await;
+        let appended = server
+            .get("/ping")
+            .add_header(X_FORWARDED_PROTO, "http, https")
+            .await;
+
+        spoofed.assert_status(StatusCode::BAD_REQUEST);
+        appended.assert_status(StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_ignores_forwarded_proto_from_untrusted_peers() -> anyhow::Result<()> {
+        let server = server(HttpsEnforcementMode::Reject, UNTRUSTED_PEER)?;
+
+        let response = server
+            .get("/ping")
+            .add_header(X_FORWARDED_PROTO, "https")
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_passes_requests_over_terminated_tls() -> anyhow::Result<()> {
+        let config = HttpsEnforcementConfig {
+            mode: HttpsEnforcementMode::Reject,
+            trusted_proxies: vec![],
+            canonical_host: None,
+        };
+        let router = Router::new()
+            .route("/ping", get(|| async { "pong" }))
+            .layer(axum::middleware::from_fn_with_state(config, enforce_https))
+            .layer(axum::Extension(TerminatedTls))
+            .layer(MockConnectInfo(SocketAddr::from((UNTRUSTED_PEER, 1234))));
+        let server = TestServer::new(router)?;
+
+        let response = server.get("/ping").await;
+
+        response.assert_status(StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn enforce_https_passes_all_requests_when_disabled() -> anyhow::Result<()> {
+        let server = server(HttpsEnforcementMode::Disabled, UNTRUSTED_PEER)?;
+
+        let response = server.get("/ping").await;
+
+        response.assert_status(StatusCode::OK);
+
+        Ok(())
+    }
+}
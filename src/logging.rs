@@ -0,0 +1,153 @@
+use crate::api_error::ApiError;
+use crate::config::{LogConfig, LogFormat};
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use tower_http::request_id::{
+    MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer,
+};
+use tower_http::trace::TraceLayer;
+use tracing_subscriber::EnvFilter;
+
+/// Initializes the global tracing subscriber with the [LogFormat] of the given [LogConfig]. The
+/// log level is taken from the `RUST_LOG` environment variable.
+pub fn init(config: &LogConfig) {
+    let subscriber = tracing_subscriber::fmt().with_env_filter(EnvFilter::from_default_env());
+
+    match config.format {
+        LogFormat::Pretty => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+/// Wraps the given [Router] so that every request gets an ID, either the one sent by the client
+/// in the `x-request-id` header or a generated one. The ID is recorded in the request span, echoed
+/// in the `x-request-id` response header and added to [ApiError] responses.
+pub fn with_request_id(router: Router) -> Router {
+    router
+        .layer(axum::middleware::from_fn(attach_request_id))
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(
+            TraceLayer::new_for_http().make_span_with(|request: &Request| {
+                let request_id = request
+                    .extensions()
+                    .get::<RequestId>()
+                    .and_then(|request_id| request_id.header_value().to_str().ok())
+                    .unwrap_or_default();
+
+                tracing::info_span!(
+                    "request",
+                    method = %request.method(),
+                    uri = %request.uri(),
+                    request_id,
+                )
+            }),
+        )
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+/// Sets [ApiError::trace_id] of [ApiError] responses to the ID of the request, keeping the
+/// status and the headers of the response.
+async fn attach_request_id(request: Request, next: Next) -> Response {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|request_id| request_id.header_value().to_str().ok())
+        .map(str::to_string);
+
+    let response = next.run(request).await;
+    let (Some(request_id), Some(api_error)) = (request_id, response.extensions().get::<ApiError>())
+    else {
+        return response;
+    };
+
+    let api_error = ApiError {
+        trace_id: request_id.into(),
+        ..api_error.clone()
+    };
+    let (mut parts, _) = response.into_parts();
+    let body = Json(&api_error).into_response().into_body();
+    parts.extensions.insert(api_error);
+
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_error::ErrorCode;
+    use axum::Extension;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum_test::TestServer;
+
+    const X_REQUEST_ID: &str = "x-request-id";
+
+    fn server() -> anyhow::Result<TestServer> {
+        let router = Router::new()
+            .route(
+                "/request_id",
+                get(|Extension(request_id): Extension<RequestId>| async move {
+                    request_id
+                        .header_value()
+                        .to_str()
+                        .unwrap_or_default()
+                        .to_string()
+                }),
+            )
+            .route(
+                "/error",
+                get(|| async {
+                    ApiError::new(ErrorCode::new("test", 0), "test error")
+                        .into_response(StatusCode::BAD_REQUEST)
+                }),
+            );
+
+        TestServer::new(with_request_id(router))
+    }
+
+    #[tokio::test]
+    async fn with_request_id_generates_request_id_seen_by_handler() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let first = server.get("/request_id").await;
+        let second = server.get("/request_id").await;
+
+        let first_request_id = first.header(X_REQUEST_ID);
+        assert!(!first_request_id.is_empty());
+        assert_eq!(first.text(), first_request_id.to_str()?);
+        assert_ne!(first_request_id, second.header(X_REQUEST_ID));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_request_id_keeps_request_id_of_client() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server
+            .get("/request_id")
+            .add_header(X_REQUEST_ID, "client-request-id")
+            .await;
+
+        assert_eq!(response.header(X_REQUEST_ID), "client-request-id");
+        assert_eq!(response.text(), "client-request-id");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_request_id_adds_trace_id_to_api_errors() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server.get("/error").await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, "test");
+        assert_eq!(error.trace_id, response.header(X_REQUEST_ID).to_str()?);
+
+        Ok(())
+    }
+}
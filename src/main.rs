@@ -1,44 +1,104 @@
 use crate::app_state::AppStateDefault;
 use crate::players::jwt_service::JwtServiceDefault;
+use crate::players::players_db::SlowQueryLoggingPlayersDb;
 use crate::players::players_service::PlayersServiceDefault;
+use crate::players::presence::PresenceServiceDefault;
+use crate::players::sign_in_rate_limiter::SignInRateLimiter;
 use axum::Router;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tracing::info;
 
 mod api_error;
 mod app_state;
+mod body_limit;
+mod catch_panic;
+mod compression;
 mod config;
+mod cors;
 mod healthcheck;
+mod https_enforcement;
+mod logging;
 mod players;
+mod routing;
+mod slow_query_log;
+mod tls;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
-
     let config = config::Config::from_env()?;
+    logging::init(&config.log);
+    config.validate()?;
     info!("Starting app with config: {config:?}");
 
-    let pg_pool = sqlx::PgPool::connect(&config.postgres.connection_url()).await?;
+    let pg_pool = config
+        .postgres
+        .pool_options()
+        .connect(&config.postgres.connection_url())
+        .await?;
     sqlx::migrate!().run(&pg_pool).await?;
+    players::players_db::verify_screen_name_column(&pg_pool).await?;
 
     let http_client = reqwest::Client::new();
-    let jwt_service = JwtServiceDefault::new(config.jwt);
+    let jwt_service = JwtServiceDefault::new(
+        config.jwt,
+        SlowQueryLoggingPlayersDb::new(pg_pool.clone(), config.postgres.slow_query_threshold),
+    )?;
     let players_service = PlayersServiceDefault::new(
-        pg_pool,
+        pg_pool.clone(),
+        config.postgres.slow_query_threshold,
         http_client,
         config.google_id_token_verifier,
+        config.apple_id_token_verifier,
         jwt_service.clone(),
+        config.id_token_replay_protection,
+        config.id_token_verification_limit,
+        config.id_token_platform_audiences,
     );
-    let app_state = Arc::new(AppStateDefault::new(players_service, jwt_service));
+    let sign_in_rate_limiter = config
+        .sign_in_rate_limit
+        .enabled
+        .then(|| SignInRateLimiter::new(config.sign_in_rate_limit));
+    let app_state = Arc::new(AppStateDefault::new(
+        players_service,
+        jwt_service,
+        PresenceServiceDefault::new(SlowQueryLoggingPlayersDb::new(
+            pg_pool.clone(),
+            config.postgres.slow_query_threshold,
+        )),
+        sign_in_rate_limiter,
+    ));
 
     let tcp_listener = TcpListener::bind(&config.server.listen_addr()).await?;
-    let router = Router::new().merge(healthcheck::router()).merge(
+    let router = Router::new().merge(healthcheck::router(pg_pool)).merge(
         Router::new()
             .merge(players::http::router())
-            .with_state(app_state),
+            .with_state(app_state)
+            .layer(axum::middleware::from_fn_with_state(
+                config.https_enforcement,
+                https_enforcement::enforce_https,
+            ))
+            .layer(cors::layer(&config.cors)?),
     );
-    axum::serve(tcp_listener, router).await?;
+    let router = routing::with_fallbacks(router);
+    let router = body_limit::with_body_limit(router, config.server.max_body_size);
+    let router = catch_panic::with_catch_panic(router);
+    let router = logging::with_request_id(router);
+    let router = api_error::with_format(router, &config.api_error);
+    let router = compression::with_compression(router, &config.compression);
+    match &config.server.tls {
+        Some(tls_config) => {
+            tls::serve(tcp_listener, tls::server_config(tls_config)?, router).await?
+        }
+        None => {
+            axum::serve(
+                tcp_listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await?
+        }
+    }
 
     Ok(())
 }
@@ -1,7 +1,14 @@
-use crate::api_error::ApiError;
-use axum::Json;
+use crate::api_error::{ApiError, ErrorCode};
+use crate::players::password::InvalidPassword;
+use crate::players::report::Report;
+use crate::players::sign_in_method::{
+    ClientPlatform, DeviceId, InvalidEmail, ThirdPartySignInProvider,
+};
 use axum::http::StatusCode;
+use axum::http::header::RETRY_AFTER;
 use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use std::time::Duration as StdDuration;
 
 /// Possible players errors.
 #[derive(Debug, thiserror::Error)]
@@ -25,24 +32,426 @@ pub enum PlayersError {
     /// Internal error.
     #[error("internal error: {0}")]
     Internal(#[from] anyhow::Error),
+
+    /// When a third party id token has already been used to sign in.
+    #[error("third party id token has already been used")]
+    IdTokenReused,
+
+    /// When too many third party id tokens are being verified concurrently.
+    #[error("too many concurrent sign-ins, try again later")]
+    IdTokenVerificationOverloaded,
+
+    /// When signing in with a third party sign in provider that is not configured.
+    #[error("sign in provider {0:?} is not configured")]
+    SignInProviderNotConfigured(ThirdPartySignInProvider),
+
+    /// When registering with an email that is already registered.
+    #[error("email is already registered")]
+    EmailAlreadyRegistered,
+
+    /// When signing in with an unknown email or a wrong password.
+    #[error("invalid email or password")]
+    InvalidCredentials,
+
+    /// When registering with an invalid email.
+    #[error("{0}")]
+    InvalidEmail(#[from] InvalidEmail),
+
+    /// When registering with an invalid password.
+    #[error("{0}")]
+    InvalidPassword(#[from] InvalidPassword),
+
+    /// When linking a sign in method that already belongs to a different player.
+    #[error("sign in method is already linked to another player")]
+    SignInMethodAlreadyLinked,
+
+    /// When refreshing with a refresh token that has already been rotated. The whole refresh
+    /// token chain is revoked as the token has likely been stolen.
+    #[error("refresh token has already been used")]
+    RefreshTokenReused,
+
+    /// When refreshing with an unknown, expired or revoked refresh token.
+    #[error("refresh token is invalid")]
+    RefreshTokenInvalid,
+
+    /// When auth token has been revoked, e.g. on logout.
+    #[error("auth token has been revoked")]
+    AuthTokenRevoked,
+
+    /// When a third party id token is issued for a known client of a different platform than the
+    /// one signing in.
+    #[error("id token is issued for a {0:?} client, use the credentials of this platform")]
+    IdTokenWrongPlatform(ClientPlatform),
+
+    /// When auth token header is not of the `Bearer <token>` form.
+    #[error("auth token header is malformed, expected `Bearer <token>`")]
+    AuthTokenMalformed,
+
+    /// When a screen name is already taken by another player.
+    #[error("screen name is already taken")]
+    ScreenNameTaken,
+
+    /// When a player is not allowed to access a resource, e.g. an admin one.
+    #[error("player is not allowed to access this resource")]
+    Forbidden,
+
+    /// When too many sign-ins have been made from the same IP, retry after the given duration.
+    #[error("too many sign-ins, retry in {} seconds", retry_after_secs(.0))]
+    SignInRateLimited(StdDuration),
+
+    /// When a looked up player does not exist. Unlike [PlayersError::PlayerNotFound], it is not
+    /// about the current player.
+    #[error("player profile not found")]
+    ProfileNotFound,
+
+    /// When more players are looked up at once than allowed.
+    #[error("too many player ids, at most {0} are allowed")]
+    TooManyPlayerIds(usize),
+
+    /// When a banned player makes a request, the ban expires at the given time.
+    #[error("player is banned until {0}")]
+    PlayerBanned(DateTime<Utc>),
+
+    /// When a player requests friendship with themselves.
+    #[error("player can't befriend themselves")]
+    CannotBefriendSelf,
+
+    /// When a friendship is requested between players that can't become friends.
+    #[error("friendship is blocked")]
+    FriendshipBlocked,
+
+    /// When a player accepts a friend request that has not been sent to them.
+    #[error("friend request not found")]
+    FriendRequestNotFound,
+
+    /// When a player tries to block themselves.
+    #[error("player can't block themselves")]
+    CannotBlockSelf,
+
+    /// When a report reason is blank or too long.
+    #[error(
+        "report reason must be non-blank and at most {} characters",
+        Report::MAX_REASON_SIZE
+    )]
+    InvalidReportReason,
+
+    /// When a player has made too many reports recently.
+    #[error("too many reports, retry later")]
+    ReportRateLimited,
+
+    /// When a report to resolve does not exist.
+    #[error("report not found")]
+    ReportNotFound,
+
+    /// When signing in as a guest with a blank or too long device id.
+    #[error(
+        "device id must be non-blank and at most {} characters",
+        DeviceId::MAX_SIZE
+    )]
+    InvalidDeviceId,
+}
+
+impl PlayersError {
+    /// [ApiError::module] of [PlayersError]s.
+    pub const MODULE: &str = "players";
+
+    /// [ErrorCode] of [PlayersError::IdToken].
+    pub const ID_TOKEN: ErrorCode = ErrorCode::new(PlayersError::MODULE, 0);
+
+    /// [ErrorCode] of [PlayersError::PlayerNotFound].
+    pub const PLAYER_NOT_FOUND: ErrorCode = ErrorCode::new(PlayersError::MODULE, 1);
+
+    /// [ErrorCode] of [PlayersError::AuthToken].
+    pub const AUTH_TOKEN: ErrorCode = ErrorCode::new(PlayersError::MODULE, 2);
+
+    /// [ErrorCode] of [PlayersError::AuthTokenMissing].
+    pub const AUTH_TOKEN_MISSING: ErrorCode = ErrorCode::new(PlayersError::MODULE, 3);
+
+    /// [ErrorCode] of [PlayersError::Internal].
+    pub const INTERNAL: ErrorCode = ErrorCode::new(PlayersError::MODULE, 4);
+
+    /// [ErrorCode] of [PlayersError::IdTokenReused].
+    pub const ID_TOKEN_REUSED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 5);
+
+    /// [ErrorCode] of [PlayersError::IdTokenVerificationOverloaded].
+    pub const ID_TOKEN_VERIFICATION_OVERLOADED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 6);
+
+    /// [ErrorCode] of [PlayersError::SignInProviderNotConfigured].
+    pub const SIGN_IN_PROVIDER_NOT_CONFIGURED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 7);
+
+    /// [ErrorCode] of [PlayersError::EmailAlreadyRegistered].
+    pub const EMAIL_ALREADY_REGISTERED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 8);
+
+    /// [ErrorCode] of [PlayersError::InvalidCredentials].
+    pub const INVALID_CREDENTIALS: ErrorCode = ErrorCode::new(PlayersError::MODULE, 9);
+
+    /// [ErrorCode] of [PlayersError::InvalidEmail].
+    pub const INVALID_EMAIL: ErrorCode = ErrorCode::new(PlayersError::MODULE, 10);
+
+    /// [ErrorCode] of [PlayersError::InvalidPassword].
+    pub const INVALID_PASSWORD: ErrorCode = ErrorCode::new(PlayersError::MODULE, 11);
+
+    /// [ErrorCode] of [PlayersError::SignInMethodAlreadyLinked].
+    pub const SIGN_IN_METHOD_ALREADY_LINKED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 12);
+
+    /// [ErrorCode] of [PlayersError::RefreshTokenReused].
+    pub const REFRESH_TOKEN_REUSED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 13);
+
+    /// [ErrorCode] of [PlayersError::RefreshTokenInvalid].
+    pub const REFRESH_TOKEN_INVALID: ErrorCode = ErrorCode::new(PlayersError::MODULE, 14);
+
+    /// [ErrorCode] of [PlayersError::AuthTokenRevoked].
+    pub const AUTH_TOKEN_REVOKED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 15);
+
+    /// [ErrorCode] of [PlayersError::IdTokenWrongPlatform].
+    pub const ID_TOKEN_WRONG_PLATFORM: ErrorCode = ErrorCode::new(PlayersError::MODULE, 16);
+
+    /// [ErrorCode] of [PlayersError::AuthTokenMalformed].
+    pub const AUTH_TOKEN_MALFORMED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 17);
+
+    /// [ErrorCode] of [PlayersError::ScreenNameTaken].
+    pub const SCREEN_NAME_TAKEN: ErrorCode = ErrorCode::new(PlayersError::MODULE, 18);
+
+    /// [ErrorCode] of [PlayersError::SignInRateLimited].
+    pub const SIGN_IN_RATE_LIMITED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 19);
+
+    /// [ErrorCode] of [PlayersError::Forbidden].
+    pub const FORBIDDEN: ErrorCode = ErrorCode::new(PlayersError::MODULE, 20);
+
+    /// [ErrorCode] of [PlayersError::ProfileNotFound].
+    pub const PROFILE_NOT_FOUND: ErrorCode = ErrorCode::new(PlayersError::MODULE, 21);
+
+    /// [ErrorCode] of [PlayersError::TooManyPlayerIds].
+    pub const TOO_MANY_PLAYER_IDS: ErrorCode = ErrorCode::new(PlayersError::MODULE, 22);
+
+    /// [ErrorCode] of [PlayersError::PlayerBanned].
+    pub const PLAYER_BANNED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 23);
+
+    /// [ErrorCode] of [PlayersError::CannotBefriendSelf].
+    pub const CANNOT_BEFRIEND_SELF: ErrorCode = ErrorCode::new(PlayersError::MODULE, 24);
+
+    /// [ErrorCode] of [PlayersError::FriendshipBlocked].
+    pub const FRIENDSHIP_BLOCKED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 25);
+
+    /// [ErrorCode] of [PlayersError::FriendRequestNotFound].
+    pub const FRIEND_REQUEST_NOT_FOUND: ErrorCode = ErrorCode::new(PlayersError::MODULE, 26);
+
+    /// [ErrorCode] of [PlayersError::CannotBlockSelf].
+    pub const CANNOT_BLOCK_SELF: ErrorCode = ErrorCode::new(PlayersError::MODULE, 27);
+
+    /// [ErrorCode] of [PlayersError::InvalidReportReason].
+    pub const INVALID_REPORT_REASON: ErrorCode = ErrorCode::new(PlayersError::MODULE, 28);
+
+    /// [ErrorCode] of [PlayersError::ReportRateLimited].
+    pub const REPORT_RATE_LIMITED: ErrorCode = ErrorCode::new(PlayersError::MODULE, 29);
+
+    /// [ErrorCode] of [PlayersError::ReportNotFound].
+    pub const REPORT_NOT_FOUND: ErrorCode = ErrorCode::new(PlayersError::MODULE, 30);
+
+    /// [ErrorCode] of [PlayersError::InvalidDeviceId].
+    pub const INVALID_DEVICE_ID: ErrorCode = ErrorCode::new(PlayersError::MODULE, 31);
+
+    /// All [ErrorCode]s of [PlayersError]s.
+    pub const CODES: [ErrorCode; 32] = [
+        PlayersError::ID_TOKEN,
+        PlayersError::PLAYER_NOT_FOUND,
+        PlayersError::AUTH_TOKEN,
+        PlayersError::AUTH_TOKEN_MISSING,
+        PlayersError::INTERNAL,
+        PlayersError::ID_TOKEN_REUSED,
+        PlayersError::ID_TOKEN_VERIFICATION_OVERLOADED,
+        PlayersError::SIGN_IN_PROVIDER_NOT_CONFIGURED,
+        PlayersError::EMAIL_ALREADY_REGISTERED,
+        PlayersError::INVALID_CREDENTIALS,
+        PlayersError::INVALID_EMAIL,
+        PlayersError::INVALID_PASSWORD,
+        PlayersError::SIGN_IN_METHOD_ALREADY_LINKED,
+        PlayersError::REFRESH_TOKEN_REUSED,
+        PlayersError::REFRESH_TOKEN_INVALID,
+        PlayersError::AUTH_TOKEN_REVOKED,
+        PlayersError::ID_TOKEN_WRONG_PLATFORM,
+        PlayersError::AUTH_TOKEN_MALFORMED,
+        PlayersError::SCREEN_NAME_TAKEN,
+        PlayersError::SIGN_IN_RATE_LIMITED,
+        PlayersError::FORBIDDEN,
+        PlayersError::PROFILE_NOT_FOUND,
+        PlayersError::TOO_MANY_PLAYER_IDS,
+        PlayersError::PLAYER_BANNED,
+        PlayersError::CANNOT_BEFRIEND_SELF,
+        PlayersError::FRIENDSHIP_BLOCKED,
+        PlayersError::FRIEND_REQUEST_NOT_FOUND,
+        PlayersError::CANNOT_BLOCK_SELF,
+        PlayersError::INVALID_REPORT_REASON,
+        PlayersError::REPORT_RATE_LIMITED,
+        PlayersError::REPORT_NOT_FOUND,
+        PlayersError::INVALID_DEVICE_ID,
+    ];
+
+    /// [StatusCode] and [ErrorCode] of this [PlayersError].
+    pub fn status_and_code(&self) -> (StatusCode, ErrorCode) {
+        match self {
+            PlayersError::IdToken(_) => (StatusCode::BAD_REQUEST, PlayersError::ID_TOKEN),
+            PlayersError::PlayerNotFound => {
+                (StatusCode::UNAUTHORIZED, PlayersError::PLAYER_NOT_FOUND)
+            }
+            PlayersError::AuthToken(_) => (StatusCode::UNAUTHORIZED, PlayersError::AUTH_TOKEN),
+            PlayersError::AuthTokenMissing => {
+                (StatusCode::UNAUTHORIZED, PlayersError::AUTH_TOKEN_MISSING)
+            }
+            PlayersError::Internal(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, PlayersError::INTERNAL)
+            }
+            PlayersError::IdTokenReused => (StatusCode::BAD_REQUEST, PlayersError::ID_TOKEN_REUSED),
+            PlayersError::IdTokenVerificationOverloaded => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                PlayersError::ID_TOKEN_VERIFICATION_OVERLOADED,
+            ),
+            PlayersError::SignInProviderNotConfigured(_) => (
+                StatusCode::BAD_REQUEST,
+                PlayersError::SIGN_IN_PROVIDER_NOT_CONFIGURED,
+            ),
+            PlayersError::EmailAlreadyRegistered => {
+                (StatusCode::CONFLICT, PlayersError::EMAIL_ALREADY_REGISTERED)
+            }
+            PlayersError::InvalidCredentials => {
+                (StatusCode::UNAUTHORIZED, PlayersError::INVALID_CREDENTIALS)
+            }
+            PlayersError::InvalidEmail(_) => (StatusCode::BAD_REQUEST, PlayersError::INVALID_EMAIL),
+            PlayersError::InvalidPassword(_) => {
+                (StatusCode::BAD_REQUEST, PlayersError::INVALID_PASSWORD)
+            }
+            PlayersError::SignInMethodAlreadyLinked => (
+                StatusCode::CONFLICT,
+                PlayersError::SIGN_IN_METHOD_ALREADY_LINKED,
+            ),
+            PlayersError::RefreshTokenReused => {
+                (StatusCode::UNAUTHORIZED, PlayersError::REFRESH_TOKEN_REUSED)
+            }
+            PlayersError::RefreshTokenInvalid => (
+                StatusCode::UNAUTHORIZED,
+                PlayersError::REFRESH_TOKEN_INVALID,
+            ),
+            PlayersError::AuthTokenRevoked => {
+                (StatusCode::UNAUTHORIZED, PlayersError::AUTH_TOKEN_REVOKED)
+            }
+            PlayersError::IdTokenWrongPlatform(_) => (
+                StatusCode::BAD_REQUEST,
+                PlayersError::ID_TOKEN_WRONG_PLATFORM,
+            ),
+            PlayersError::AuthTokenMalformed => {
+                (StatusCode::UNAUTHORIZED, PlayersError::AUTH_TOKEN_MALFORMED)
+            }
+            PlayersError::ScreenNameTaken => {
+                (StatusCode::CONFLICT, PlayersError::SCREEN_NAME_TAKEN)
+            }
+            PlayersError::SignInRateLimited(_) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                PlayersError::SIGN_IN_RATE_LIMITED,
+            ),
+            PlayersError::Forbidden => (StatusCode::FORBIDDEN, PlayersError::FORBIDDEN),
+            PlayersError::ProfileNotFound => {
+                (StatusCode::NOT_FOUND, PlayersError::PROFILE_NOT_FOUND)
+            }
+            PlayersError::TooManyPlayerIds(_) => {
+                (StatusCode::BAD_REQUEST, PlayersError::TOO_MANY_PLAYER_IDS)
+            }
+            PlayersError::PlayerBanned(_) => (StatusCode::FORBIDDEN, PlayersError::PLAYER_BANNED),
+            PlayersError::CannotBefriendSelf => {
+                (StatusCode::BAD_REQUEST, PlayersError::CANNOT_BEFRIEND_SELF)
+            }
+            PlayersError::FriendshipBlocked => {
+                (StatusCode::CONFLICT, PlayersError::FRIENDSHIP_BLOCKED)
+            }
+            PlayersError::FriendRequestNotFound => (
+                StatusCode::NOT_FOUND,
+                PlayersError::FRIEND_REQUEST_NOT_FOUND,
+            ),
+            PlayersError::CannotBlockSelf => {
+                (StatusCode::BAD_REQUEST, PlayersError::CANNOT_BLOCK_SELF)
+            }
+            PlayersError::InvalidReportReason => {
+                (StatusCode::BAD_REQUEST, PlayersError::INVALID_REPORT_REASON)
+            }
+            PlayersError::ReportRateLimited => (
+                StatusCode::TOO_MANY_REQUESTS,
+                PlayersError::REPORT_RATE_LIMITED,
+            ),
+            PlayersError::ReportNotFound => (StatusCode::NOT_FOUND, PlayersError::REPORT_NOT_FOUND),
+            PlayersError::InvalidDeviceId => {
+                (StatusCode::BAD_REQUEST, PlayersError::INVALID_DEVICE_ID)
+            }
+        }
+    }
 }
 
+const _: () = assert!(
+    ErrorCode::ids_unique(&PlayersError::CODES),
+    "PlayersError error code ids must be unique"
+);
+
 impl IntoResponse for PlayersError {
     fn into_response(self) -> Response {
-        let (status, id) = match &self {
-            PlayersError::IdToken(_) => (StatusCode::BAD_REQUEST, 0),
-            PlayersError::PlayerNotFound => (StatusCode::UNAUTHORIZED, 1),
-            PlayersError::AuthToken(_) => (StatusCode::UNAUTHORIZED, 2),
-            PlayersError::AuthTokenMissing => (StatusCode::UNAUTHORIZED, 3),
-            PlayersError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, 4),
+        let (status, code) = self.status_and_code();
+        let retry_after = match &self {
+            PlayersError::SignInRateLimited(retry_after) => Some(retry_after_secs(retry_after)),
+            _ => None,
         };
 
-        let body = ApiError {
-            module: "players".into(),
-            id,
-            dev_message: self.to_string().into(),
-        };
+        let mut response = ApiError::new(code, self.to_string()).into_response(status);
+        if let Some(retry_after) = retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, retry_after.into());
+        }
+
+        response
+    }
+}
+
+/// Whole seconds to wait for the given duration, at least 1.
+fn retry_after_secs(retry_after: &StdDuration) -> u64 {
+    retry_after.as_secs_f64().ceil().max(1.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_error::ProblemDetails;
+    use crate::config::ApiErrorConfig;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::routing::get;
+    use axum_test::TestServer;
+
+    #[test]
+    fn players_error_codes_keep_their_ids() {
+        let ids = PlayersError::CODES.map(|code| code.id);
+
+        assert_eq!(ids, std::array::from_fn(|i| i as u32));
+        assert!(
+            PlayersError::CODES
+                .iter()
+                .all(|code| code.module == "players")
+        );
+        assert_eq!(PlayersError::SCREEN_NAME_TAKEN.id, 18);
+    }
+
+    #[tokio::test]
+    async fn players_error_problem_json_snapshot() -> anyhow::Result<()> {
+        let router = axum::Router::new().route(
+            "/players/player_info",
+            get(|| async { PlayersError::AuthTokenMissing }),
+        );
+        let server = TestServer::new(crate::api_error::with_format(
+            router,
+            &ApiErrorConfig { problem_json: true },
+        ))?;
+
+        let response = server.get("/players/player_info").await;
+
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(response.header(CONTENT_TYPE), "application/problem+json");
+        insta::assert_json_snapshot!(response.json::<ProblemDetails>());
 
-        (status, Json(body)).into_response()
+        Ok(())
     }
 }
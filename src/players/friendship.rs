@@ -0,0 +1,54 @@
+use crate::players::player::{PlayerId, PublicPlayer};
+use serde::{Deserialize, Serialize};
+
+/// State of a [Friendship].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "friendship_state")]
+pub enum FriendshipState {
+    /// The addressee has not accepted the friend request yet.
+    Pending,
+
+    /// The players are friends.
+    Accepted,
+
+    /// The players can't become friends.
+    Blocked,
+}
+
+/// Friendship between two players, requested by one of them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Friendship {
+    /// [PlayerId] of the player who requested the friendship.
+    pub requester_id: PlayerId,
+
+    /// [PlayerId] of the player the friendship was requested from.
+    pub addressee_id: PlayerId,
+
+    /// [FriendshipState] of the friendship.
+    pub state: FriendshipState,
+}
+
+impl Friendship {
+    #[cfg(test)]
+    /// Returns a test [Friendship] requested by [PlayerId::test].
+    pub fn test() -> Friendship {
+        Friendship {
+            requester_id: PlayerId::test(),
+            addressee_id: PlayerId(uuid::Uuid::from_u128(9876543210)),
+            state: FriendshipState::Pending,
+        }
+    }
+}
+
+/// Other player of a [Friendship] as shown to a player.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Friend {
+    /// The other player.
+    pub player: PublicPlayer,
+
+    /// [FriendshipState] of the [Friendship].
+    pub state: FriendshipState,
+
+    /// Whether the other player has requested the [Friendship].
+    pub incoming: bool,
+}
@@ -1,14 +1,27 @@
 use crate::app_state::AppState;
 use crate::players::error::PlayersError;
-use crate::players::jwt_service::AuthToken;
-use crate::players::player::Player;
-use crate::players::players_service::PlayersService;
-use axum::extract::State;
+use crate::players::friendship::Friend;
+use crate::players::jwt_service::{AuthToken, AuthTokenClaims, JwtService, TokenPair};
+use crate::players::notification::{NotificationId, NotificationsPage};
+use crate::players::page::Page;
+use crate::players::password::Password;
+use crate::players::player::{AdminPlayer, Player, PlayerId, PlayerScreenName, PublicPlayer};
+use crate::players::players_service::{PlayersService, SignedIn};
+use crate::players::preferences::PlayerPreferences;
+use crate::players::presence::{Presence, PresenceService};
+use crate::players::refresh_token::RefreshToken;
+use crate::players::report::{Report, ReportId};
+use crate::players::sign_in_method::{ClientPlatform, DeviceId, LinkedSignInMethod};
+use crate::players::sign_in_rate_limiter::SignInRateLimit;
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::routing::*;
 use axum::{Json, Router};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
 
 /// A sign-in request.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,6 +30,44 @@ pub enum SignInRequest {
     Google {
         /// Google issued [IdToken].
         id_token: IdToken,
+
+        /// [ClientPlatform] signing in, the [IdToken] audience is checked against it if set.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        platform: Option<ClientPlatform>,
+    },
+
+    /// Sign in with Apple.
+    Apple {
+        /// Apple issued [IdToken].
+        id_token: IdToken,
+
+        /// [ClientPlatform] signing in, the [IdToken] audience is checked against it if set.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        platform: Option<ClientPlatform>,
+    },
+
+    /// Register a new player with email and password.
+    Register {
+        /// Email to register with.
+        email: String,
+
+        /// [Password] to register with.
+        password: Password,
+    },
+
+    /// Sign in with email and password of an already registered player.
+    EmailLogin {
+        /// Registered email.
+        email: String,
+
+        /// [Password] of the registered player.
+        password: Password,
+    },
+
+    /// Sign in as a guest from the given device.
+    Guest {
+        /// [DeviceId] of the guest's device.
+        device_id: DeviceId,
     },
 }
 
@@ -26,6 +77,68 @@ impl SignInRequest {
     pub fn test_google() -> SignInRequest {
         SignInRequest::Google {
             id_token: IdToken::test(),
+            platform: None,
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns a test [SignInRequest::Register].
+    pub fn test_register() -> SignInRequest {
+        SignInRequest::Register {
+            email: "player@example.com".into(),
+            password: Password::test(),
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns a test [SignInRequest::EmailLogin].
+    pub fn test_email_login() -> SignInRequest {
+        SignInRequest::EmailLogin {
+            email: "player@example.com".into(),
+            password: Password::test(),
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns a test [SignInRequest::Apple].
+    pub fn test_apple() -> SignInRequest {
+        SignInRequest::Apple {
+            id_token: IdToken::test(),
+            platform: None,
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns a test [SignInRequest::Guest].
+    pub fn test_guest() -> SignInRequest {
+        SignInRequest::Guest {
+            device_id: DeviceId::test(),
+        }
+    }
+}
+
+/// A request to link a third party sign in method to the current player.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LinkSignInMethodRequest {
+    /// Link a Google account.
+    Google {
+        /// Google issued [IdToken].
+        id_token: IdToken,
+    },
+
+    /// Link an Apple account.
+    Apple {
+        /// Apple issued [IdToken].
+        id_token: IdToken,
+    },
+}
+
+impl LinkSignInMethodRequest {
+    #[cfg(test)]
+    /// Returns a test [LinkSignInMethodRequest::Google].
+    pub fn test_google() -> LinkSignInMethodRequest {
+        LinkSignInMethodRequest::Google {
+            id_token: IdToken::test(),
         }
     }
 }
@@ -35,6 +148,15 @@ impl SignInRequest {
 pub struct SignInResponse<'a> {
     /// [AuthToken] to use in subsequent requests.
     pub auth_token: AuthToken<'a>,
+
+    /// [RefreshToken] to obtain a new [AuthToken] with once it expires.
+    pub refresh_token: RefreshToken,
+
+    /// The signed in [Player].
+    pub player: Player,
+
+    /// Whether the [Player] has been created by this sign in, e.g. to show onboarding.
+    pub is_new_player: bool,
 }
 
 impl SignInResponse<'_> {
@@ -43,6 +165,247 @@ impl SignInResponse<'_> {
     pub fn test() -> SignInResponse<'static> {
         SignInResponse {
             auth_token: AuthToken::test(),
+            refresh_token: RefreshToken::test(),
+            player: Player::test(),
+            is_new_player: false,
+        }
+    }
+}
+
+impl From<SignedIn> for SignInResponse<'static> {
+    fn from(signed_in: SignedIn) -> SignInResponse<'static> {
+        SignInResponse {
+            auth_token: signed_in.token_pair.auth_token,
+            refresh_token: signed_in.token_pair.refresh_token,
+            player: signed_in.player,
+            is_new_player: signed_in.is_new_player,
+        }
+    }
+}
+
+/// Response to a [RefreshRequest] in case of success.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RefreshResponse<'a> {
+    /// [AuthToken] to use in subsequent requests.
+    pub auth_token: AuthToken<'a>,
+
+    /// [RefreshToken] to obtain a new [AuthToken] with once it expires.
+    pub refresh_token: RefreshToken,
+}
+
+impl RefreshResponse<'_> {
+    #[cfg(test)]
+    /// Returns a test [RefreshResponse].
+    pub fn test() -> RefreshResponse<'static> {
+        RefreshResponse {
+            auth_token: AuthToken::test(),
+            refresh_token: RefreshToken::test(),
+        }
+    }
+}
+
+impl From<TokenPair> for RefreshResponse<'static> {
+    fn from(token_pair: TokenPair) -> RefreshResponse<'static> {
+        RefreshResponse {
+            auth_token: token_pair.auth_token,
+            refresh_token: token_pair.refresh_token,
+        }
+    }
+}
+
+/// A request to exchange a [RefreshToken] for a new [AuthToken].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefreshRequest {
+    /// [RefreshToken] received on sign in or on the previous refresh.
+    pub refresh_token: RefreshToken,
+}
+
+impl RefreshRequest {
+    #[cfg(test)]
+    /// Returns a test [RefreshRequest].
+    pub fn test() -> RefreshRequest {
+        RefreshRequest {
+            refresh_token: RefreshToken::test(),
+        }
+    }
+}
+
+/// A request to look up several [Player]s at once.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayersBatchRequest {
+    /// [PlayerId]s of the [Player]s to look up.
+    pub ids: Vec<PlayerId>,
+}
+
+impl PlayersBatchRequest {
+    /// Max number of distinct [PlayerId]s in a [PlayersBatchRequest].
+    pub const MAX_IDS: usize = 100;
+
+    /// Returns the distinct [PlayerId]s of this [PlayersBatchRequest]. Fails with
+    /// [PlayersError::TooManyPlayerIds] if there are more than [PlayersBatchRequest::MAX_IDS].
+    pub fn distinct_ids(&self) -> Result<Vec<PlayerId>, PlayersError> {
+        let mut ids = self.ids.clone();
+        ids.sort_unstable_by_key(|id| id.0);
+        ids.dedup();
+
+        if ids.len() > PlayersBatchRequest::MAX_IDS {
+            Err(PlayersError::TooManyPlayerIds(PlayersBatchRequest::MAX_IDS))?
+        }
+
+        Ok(ids)
+    }
+}
+
+/// A request to ban a [Player].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BanRequest {
+    /// Until when the [Player] is banned, [None] lifts the ban.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Query of the `/screen_name_available` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScreenNameAvailabilityQuery {
+    /// Candidate [PlayerScreenName].
+    pub name: String,
+}
+
+/// Whether a candidate [PlayerScreenName] can be used.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScreenNameAvailability {
+    /// Whether the [PlayerScreenName] is valid and not taken.
+    pub available: bool,
+
+    /// Why the [PlayerScreenName] is not available, if it isn't.
+    pub reason: Option<String>,
+}
+
+impl ScreenNameAvailability {
+    /// Returns an available [ScreenNameAvailability].
+    pub fn available() -> ScreenNameAvailability {
+        ScreenNameAvailability {
+            available: true,
+            reason: None,
+        }
+    }
+
+    /// Returns an unavailable [ScreenNameAvailability] with the given reason.
+    pub fn unavailable(reason: String) -> ScreenNameAvailability {
+        ScreenNameAvailability {
+            available: false,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// Query of a [NotificationsPage].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NotificationsQuery {
+    /// Whether to return unread notifications only.
+    #[serde(default)]
+    pub unread_only: bool,
+
+    /// [NotificationId] to return notifications older than, see [NotificationsPage::next_before].
+    pub before: Option<NotificationId>,
+
+    /// Max number of notifications to return, [NotificationsPage::DEFAULT_LIMIT] by default and
+    /// at most [NotificationsPage::MAX_LIMIT].
+    pub limit: Option<i64>,
+}
+
+/// Query of the admin listing of [Player]s.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PlayersQuery {
+    /// [PlayerId] to return players after, see [Page::next_cursor].
+    pub after: Option<PlayerId>,
+
+    /// Max number of players to return, [PlayersQuery::DEFAULT_LIMIT] by default and at most
+    /// [PlayersQuery::MAX_LIMIT].
+    pub limit: Option<i64>,
+}
+
+impl PlayersQuery {
+    /// Number of [Player]s in a page if not requested otherwise.
+    pub const DEFAULT_LIMIT: u16 = 50;
+
+    /// Max number of [Player]s in a page.
+    pub const MAX_LIMIT: u16 = 200;
+
+    /// Returns the requested limit clamped to `1..=`[PlayersQuery::MAX_LIMIT].
+    pub fn limit(&self) -> u16 {
+        self.limit
+            .unwrap_or(i64::from(PlayersQuery::DEFAULT_LIMIT))
+            .clamp(1, i64::from(PlayersQuery::MAX_LIMIT)) as u16
+    }
+}
+
+/// A request to report a player's misconduct.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReportRequest {
+    /// [PlayerId] of the player to report.
+    pub reported_id: PlayerId,
+
+    /// ID of the game the misconduct happened in, if any.
+    #[serde(default)]
+    pub game_id: Option<Uuid>,
+
+    /// What the reported player did, see [Report::reason].
+    pub reason: String,
+}
+
+impl ReportRequest {
+    #[cfg(test)]
+    /// Returns a test [ReportRequest].
+    pub fn test() -> ReportRequest {
+        let report = Report::test();
+
+        ReportRequest {
+            reported_id: report.reported_id,
+            game_id: report.game_id,
+            reason: report.reason,
+        }
+    }
+}
+
+/// Query of the moderation queue of [Report]s.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReportsQuery {
+    /// [ReportId] to return reports after, see [Page::next_cursor].
+    pub after: Option<ReportId>,
+
+    /// Max number of reports to return, [ReportsQuery::DEFAULT_LIMIT] by default and at most
+    /// [ReportsQuery::MAX_LIMIT].
+    pub limit: Option<i64>,
+}
+
+impl ReportsQuery {
+    /// Number of [Report]s in a page if not requested otherwise.
+    pub const DEFAULT_LIMIT: u16 = 50;
+
+    /// Max number of [Report]s in a page.
+    pub const MAX_LIMIT: u16 = 200;
+
+    /// Returns the requested limit clamped to `1..=`[ReportsQuery::MAX_LIMIT].
+    pub fn limit(&self) -> u16 {
+        self.limit
+            .unwrap_or(i64::from(ReportsQuery::DEFAULT_LIMIT))
+            .clamp(1, i64::from(ReportsQuery::MAX_LIMIT)) as u16
+    }
+}
+
+/// A request to mark notifications of the current player as read.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MarkNotificationsReadRequest {
+    /// [NotificationId]s of the notifications to mark as read.
+    pub ids: Vec<NotificationId>,
+}
+
+impl MarkNotificationsReadRequest {
+    #[cfg(test)]
+    /// Returns a test [MarkNotificationsReadRequest].
+    pub fn test() -> MarkNotificationsReadRequest {
+        MarkNotificationsReadRequest {
+            ids: vec![NotificationId::test()],
         }
     }
 }
@@ -61,108 +424,1509 @@ impl IdToken {
     }
 }
 
+/// Validation constraints of [Player]s fields, so clients can validate input the same way.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerConstraints {
+    /// [PlayerScreenName] constraints.
+    pub screen_name: ScreenNameConstraints,
+}
+
+/// [PlayerScreenName] validation constraints.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScreenNameConstraints {
+    /// Min size of a [PlayerScreenName] in characters, see [PlayerScreenName::MIN_SIZE].
+    pub min_size: usize,
+
+    /// Max size of a [PlayerScreenName] in characters, see [PlayerScreenName::MAX_SIZE].
+    pub max_size: usize,
+
+    /// Whether leading and trailing whitespace is trimmed before validation.
+    pub trims_whitespace: bool,
+
+    /// [ScreenNameCharPolicy] of the characters allowed in a [PlayerScreenName].
+    pub allowed_chars: ScreenNameCharPolicy,
+}
+
+/// Characters allowed in a [PlayerScreenName], see [PlayerScreenName::is_allowed_char].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScreenNameCharPolicy {
+    /// Whether control characters (Unicode category `Cc`) are allowed.
+    pub allows_control: bool,
+
+    /// Whether whitespace characters (Unicode property `White_Space`) other than a space are
+    /// allowed.
+    pub allows_non_space_whitespace: bool,
+
+    /// Inclusive ranges of the code points of other characters that are not allowed, see
+    /// [PlayerScreenName::DISALLOWED_FORMAT_CHARS].
+    pub disallowed_ranges: Vec<[u32; 2]>,
+}
+
+impl PlayerConstraints {
+    /// Returns the [PlayerConstraints] currently enforced by the server.
+    pub fn current() -> PlayerConstraints {
+        PlayerConstraints {
+            screen_name: ScreenNameConstraints {
+                min_size: PlayerScreenName::MIN_SIZE,
+                max_size: PlayerScreenName::MAX_SIZE,
+                trims_whitespace: true,
+                allowed_chars: ScreenNameCharPolicy {
+                    allows_control: false,
+                    allows_non_space_whitespace: false,
+                    disallowed_ranges: PlayerScreenName::DISALLOWED_FORMAT_CHARS
+                        .iter()
+                        .map(|(first, last)| [u32::from(*first), u32::from(*last)])
+                        .collect(),
+                },
+            },
+        }
+    }
+}
+
 /// [Router] for the [crate::players] module.
 pub fn router<S: AppState>() -> Router<S> {
-    Router::new().nest(
-        "/players",
-        Router::new()
-            .route("/sign_in", post(sign_in::<S>))
-            .route("/player_info", get(player_info)),
-    )
+    Router::new()
+        .nest(
+            "/players",
+            Router::new()
+                .route("/", get(list_players::<S>))
+                .route("/sign_in", post(sign_in::<S>))
+                .route("/refresh", post(refresh::<S>))
+                .route("/logout", post(logout::<S>))
+                .route(
+                    "/sign_in_methods",
+                    get(sign_in_methods::<S>).post(link_sign_in_method::<S>),
+                )
+                .route("/player_info", get(player_info))
+                .route("/me", delete(delete_player::<S>))
+                .route(
+                    "/me/preferences",
+                    get(preferences::<S>).put(set_preferences::<S>),
+                )
+                .route("/me/notifications", get(notifications::<S>))
+                .route("/me/notifications/read", post(mark_notifications_read::<S>))
+                .route("/constraints", get(constraints))
+                .route("/screen_name_available", get(screen_name_available::<S>))
+                .route("/batch", post(players_batch::<S>))
+                .route("/online_count", get(online_count::<S>))
+                .route("/friends", get(friends::<S>))
+                .route(
+                    "/friends/{id}",
+                    post(request_friendship::<S>).delete(remove_friendship::<S>),
+                )
+                .route("/friends/{id}/accept", post(accept_friendship::<S>))
+                .route(
+                    "/blocks/{id}",
+                    post(block_player::<S>).delete(unblock_player::<S>),
+                )
+                .route("/{id}", get(public_player::<S>))
+                .route("/{id}/ban", put(ban_player::<S>))
+                .route("/{id}/presence", get(presence::<S>))
+                .route("/reports", post(report_player::<S>)),
+        )
+        .nest(
+            "/admin",
+            Router::new()
+                .route("/reports", get(open_reports::<S>))
+                .route("/reports/{id}/resolve", post(resolve_report::<S>)),
+        )
 }
 
 /// `/sign_in` handler. Handles [SignInRequest] and returns [SignInResponse] in case of success.
 async fn sign_in<S: AppState>(
+    _: SignInRateLimit,
     State(app_state): State<S>,
     Json(request): Json<SignInRequest>,
 ) -> Result<Response, PlayersError> {
-    let auth_token = app_state.players_service().sign_in(&request).await?;
+    let signed_in = app_state.players_service().sign_in(&request).await?;
 
-    let body = SignInResponse { auth_token };
+    let body = SignInResponse::from(signed_in);
     let response = (StatusCode::OK, Json(body)).into_response();
 
     Ok(response)
 }
 
+/// `/refresh` handler. Handles [RefreshRequest] and returns [RefreshResponse] in case of success.
+async fn refresh<S: AppState>(
+    State(app_state): State<S>,
+    Json(request): Json<RefreshRequest>,
+) -> Result<Json<RefreshResponse<'static>>, PlayersError> {
+    let token_pair = app_state
+        .players_service()
+        .refresh(&request.refresh_token)
+        .await?;
+
+    Ok(Json(RefreshResponse::from(token_pair)))
+}
+
+/// `/logout` handler. Revokes the current [AuthToken] and the refresh tokens of its sign in.
+async fn logout<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+) -> Result<StatusCode, PlayersError> {
+    app_state.jwt_service().revoke_token(&claims).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /sign_in_methods` handler. Returns the [LinkedSignInMethod]s of the current player.
+async fn sign_in_methods<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+) -> Result<Json<Vec<LinkedSignInMethod>>, PlayersError> {
+    let sign_in_methods = app_state
+        .players_service()
+        .sign_in_methods(&claims.sub)
+        .await?;
+
+    Ok(Json(sign_in_methods))
+}
+
+/// `POST /sign_in_methods` handler. Links the sign in method from the [LinkSignInMethodRequest]
+/// to the current player.
+async fn link_sign_in_method<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Json(request): Json<LinkSignInMethodRequest>,
+) -> Result<StatusCode, PlayersError> {
+    app_state
+        .players_service()
+        .link_sign_in_method(&claims.sub, &request)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// `/player_info` handler. Returns current [Player] information.
 async fn player_info(player: Player) -> Json<Player> {
     Json(player)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::api_error::ApiError;
-    use crate::app_state::MockAppState;
-    use crate::players::jwt_service::{AuthTokenClaims, MockJwtService};
-    use crate::players::player::PlayerId;
-    use crate::players::players_service::MockPlayersService;
-    use axum_test::TestServer;
-    use axum_test::http::header::AUTHORIZATION;
-    use id_token_verifier::IdTokenVerifierError;
-    use id_token_verifier::validation::ValidationError;
-    use jsonwebtoken::errors::ErrorKind;
-    use mockall::predicate::eq;
-    use std::sync::Arc;
+/// Parses the [PlayerId] from the given path segment, ids that are not a [PlayerId] fail with
+/// [PlayersError::ProfileNotFound] as no player has them.
+fn path_player_id(id: &str) -> Result<PlayerId, PlayersError> {
+    PlayerId::from_str(id).map_err(|_| PlayersError::ProfileNotFound)
+}
+
+/// `GET /friends` handler. Returns the [Friend]s of the current player.
+async fn friends<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+) -> Result<Json<Vec<Friend>>, PlayersError> {
+    let friends = app_state.players_service().friends(&claims.sub).await?;
+
+    Ok(Json(friends))
+}
+
+/// `POST /friends/{id}` handler. Requests friendship of the current player with the player with
+/// the given [PlayerId].
+async fn request_friendship<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Path(id): Path<String>,
+) -> Result<StatusCode, PlayersError> {
+    let other_id = path_player_id(&id)?;
+    app_state
+        .players_service()
+        .request_friendship(&claims.sub, &other_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /friends/{id}/accept` handler. Accepts the friend request of the player with the given
+/// [PlayerId] to the current player.
+async fn accept_friendship<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Path(id): Path<String>,
+) -> Result<StatusCode, PlayersError> {
+    let requester_id = path_player_id(&id)?;
+    app_state
+        .players_service()
+        .accept_friendship(&claims.sub, &requester_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `DELETE /friends/{id}` handler. Removes the friendship of the current player with the player
+/// with the given [PlayerId].
+async fn remove_friendship<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Path(id): Path<String>,
+) -> Result<StatusCode, PlayersError> {
+    let other_id = path_player_id(&id)?;
+    app_state
+        .players_service()
+        .remove_friendship(&claims.sub, &other_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /blocks/{id}` handler. Blocks the player with the given [PlayerId] for the current
+/// player.
+async fn block_player<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Path(id): Path<String>,
+) -> Result<StatusCode, PlayersError> {
+    let other_id = path_player_id(&id)?;
+    app_state
+        .players_service()
+        .block_player(&claims.sub, &other_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `DELETE /blocks/{id}` handler. Lifts the block of the player with the given [PlayerId] by the
+/// current player.
+async fn unblock_player<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Path(id): Path<String>,
+) -> Result<StatusCode, PlayersError> {
+    let other_id = path_player_id(&id)?;
+    app_state
+        .players_service()
+        .unblock_player(&claims.sub, &other_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /{id}` handler. Returns the [PublicPlayer] view of the [Player] with the given
+/// [PlayerId].
+async fn public_player<S: AppState>(
+    State(app_state): State<S>,
+    Path(id): Path<String>,
+) -> Result<Json<PublicPlayer>, PlayersError> {
+    let player_id = path_player_id(&id)?;
+    let player = match app_state.players_service().player_by_id(&player_id).await {
+        Ok(player) => player,
+        Err(PlayersError::PlayerNotFound) => Err(PlayersError::ProfileNotFound)?,
+        Err(e) => Err(e)?,
+    };
+
+    Ok(Json(PublicPlayer::from(player)))
+}
+
+/// `GET /{id}/presence` handler. Returns the [Presence] of the [Player] with the given
+/// [PlayerId].
+async fn presence<S: AppState>(
+    State(app_state): State<S>,
+    Path(id): Path<String>,
+) -> Result<Json<Presence>, PlayersError> {
+    let player_id = path_player_id(&id)?;
+    let presence = app_state.presence_service().presence(&player_id).await?;
+
+    Ok(Json(presence))
+}
+
+/// Number of online players, returned by the `GET /online_count` handler.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OnlineCountResponse {
+    /// Number of [Presence::Online] [Player]s.
+    pub online: i64,
+}
+
+/// `GET /online_count` handler. Returns the [OnlineCountResponse] for dashboards.
+async fn online_count<S: AppState>(
+    State(app_state): State<S>,
+) -> Result<Json<OnlineCountResponse>, PlayersError> {
+    let online = app_state.presence_service().online_count().await?;
+
+    Ok(Json(OnlineCountResponse { online }))
+}
+
+/// `PUT /{id}/ban` admin handler. Bans the [Player] with the given [PlayerId] as requested in the
+/// [BanRequest].
+async fn ban_player<S: AppState>(
+    State(app_state): State<S>,
+    _: AdminPlayer,
+    Path(id): Path<String>,
+    Json(request): Json<BanRequest>,
+) -> Result<StatusCode, PlayersError> {
+    let player_id = path_player_id(&id)?;
+    match app_state
+        .players_service()
+        .ban_player(&player_id, request.until)
+        .await
+    {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(PlayersError::PlayerNotFound) => Err(PlayersError::ProfileNotFound),
+        Err(e) => Err(e),
+    }
+}
+
+/// `POST /batch` handler. Returns the [PublicPlayer] views of the [Player]s from the
+/// [PlayersBatchRequest], skipping unknown ones.
+async fn players_batch<S: AppState>(
+    State(app_state): State<S>,
+    Json(request): Json<PlayersBatchRequest>,
+) -> Result<Json<Vec<PublicPlayer>>, PlayersError> {
+    let player_ids = request.distinct_ids()?;
+    let players = app_state
+        .players_service()
+        .players_by_ids(&player_ids)
+        .await?;
+
+    Ok(Json(players.into_iter().map(PublicPlayer::from).collect()))
+}
+
+/// `GET /` admin handler. Returns a [Page] of [Player]s matching the [PlayersQuery].
+async fn list_players<S: AppState>(
+    State(app_state): State<S>,
+    _: AdminPlayer,
+    Query(query): Query<PlayersQuery>,
+) -> Result<Json<Page<Player>>, PlayersError> {
+    let page = app_state.players_service().list_players(&query).await?;
+
+    Ok(Json(page))
+}
+
+/// `POST /reports` handler. Reports a player as requested in the [ReportRequest] and returns
+/// the created [Report].
+async fn report_player<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Json(request): Json<ReportRequest>,
+) -> Result<(StatusCode, Json<Report>), PlayersError> {
+    let report = app_state
+        .players_service()
+        .report_player(&claims.sub, &request)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// `GET /admin/reports` admin handler. Returns a [Page] of open [Report]s matching the
+/// [ReportsQuery], oldest first.
+async fn open_reports<S: AppState>(
+    State(app_state): State<S>,
+    _: AdminPlayer,
+    Query(query): Query<ReportsQuery>,
+) -> Result<Json<Page<Report>>, PlayersError> {
+    let page = app_state.players_service().open_reports(&query).await?;
+
+    Ok(Json(page))
+}
+
+/// `POST /admin/reports/{id}/resolve` admin handler. Resolves the [Report] with the given
+/// [ReportId], ids that are not a [ReportId] fail with [PlayersError::ReportNotFound].
+async fn resolve_report<S: AppState>(
+    State(app_state): State<S>,
+    _: AdminPlayer,
+    Path(id): Path<String>,
+) -> Result<StatusCode, PlayersError> {
+    let report_id = Uuid::from_str(&id)
+        .map(ReportId)
+        .map_err(|_| PlayersError::ReportNotFound)?;
+    app_state
+        .players_service()
+        .resolve_report(&report_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `/me` handler. Deletes the current [Player] with all their data.
+async fn delete_player<S: AppState>(
+    State(app_state): State<S>,
+    player: Player,
+) -> Result<StatusCode, PlayersError> {
+    app_state
+        .players_service()
+        .delete_player(&player.id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `/screen_name_available` handler. Returns the [ScreenNameAvailability] of the candidate from
+/// the [ScreenNameAvailabilityQuery].
+async fn screen_name_available<S: AppState>(
+    State(app_state): State<S>,
+    Query(query): Query<ScreenNameAvailabilityQuery>,
+) -> Result<Json<ScreenNameAvailability>, PlayersError> {
+    let availability = app_state
+        .players_service()
+        .screen_name_availability(&query.name)
+        .await?;
+
+    Ok(Json(availability))
+}
+
+/// `GET /me/preferences` handler. Returns the [PlayerPreferences] of the current player.
+async fn preferences<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+) -> Result<Json<PlayerPreferences>, PlayersError> {
+    let preferences = app_state.players_service().preferences(&claims.sub).await?;
+
+    Ok(Json(preferences))
+}
+
+/// `PUT /me/preferences` handler. Replaces the [PlayerPreferences] of the current player.
+async fn set_preferences<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Json(preferences): Json<PlayerPreferences>,
+) -> Result<StatusCode, PlayersError> {
+    app_state
+        .players_service()
+        .set_preferences(&claims.sub, &preferences)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `/me/notifications` handler. Returns a [NotificationsPage] of the current player matching the
+/// [NotificationsQuery].
+async fn notifications<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Query(query): Query<NotificationsQuery>,
+) -> Result<Json<NotificationsPage>, PlayersError> {
+    let page = app_state
+        .players_service()
+        .notifications(&claims.sub, &query)
+        .await?;
+
+    Ok(Json(page))
+}
+
+/// `/me/notifications/read` handler. Marks the notifications from the
+/// [MarkNotificationsReadRequest] of the current player as read.
+async fn mark_notifications_read<S: AppState>(
+    State(app_state): State<S>,
+    claims: AuthTokenClaims,
+    Json(request): Json<MarkNotificationsReadRequest>,
+) -> Result<StatusCode, PlayersError> {
+    app_state
+        .players_service()
+        .mark_notifications_read(&claims.sub, &request.ids)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `/constraints` handler. Returns the current [PlayerConstraints].
+async fn constraints() -> Json<PlayerConstraints> {
+    Json(PlayerConstraints::current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_error::ApiError;
+    use crate::app_state::MockAppState;
+    use crate::players::friendship::Friendship;
+    use crate::players::jwt_service::MockJwtService;
+    use crate::players::notification::Notification;
+    use crate::players::player::PlayerRole;
+    use crate::players::players_service::MockPlayersService;
+    use crate::players::presence::MockPresenceService;
+    use crate::players::sign_in_method::{Email, ThirdPartySignInProvider};
+    use axum_test::TestServer;
+    use axum_test::http::header::AUTHORIZATION;
+    use id_token_verifier::IdTokenVerifierError;
+    use id_token_verifier::validation::ValidationError;
+    use jsonwebtoken::errors::ErrorKind;
+    use mockall::predicate::eq;
+    use std::sync::Arc;
+
+    #[test]
+    fn sign_in_request_json_snapshot() {
+        insta::assert_json_snapshot!(&SignInRequest::test_google());
+    }
+
+    #[test]
+    fn sign_in_request_debug_does_not_leak_password() {
+        let debug = format!(
+            "{:?} {:?}",
+            SignInRequest::test_register(),
+            SignInRequest::test_email_login()
+        );
+
+        assert!(!debug.contains("test-password"));
+        assert!(debug.contains("<password_redacted>"));
+    }
+
+    #[test]
+    fn sign_in_response_json_snapshot() {
+        insta::assert_json_snapshot!(&SignInResponse::test());
+    }
+
+    #[test]
+    fn refresh_response_json_snapshot() {
+        insta::assert_json_snapshot!(&RefreshResponse::test());
+    }
+
+    #[test]
+    fn player_constraints_json_snapshot() {
+        insta::assert_json_snapshot!(&PlayerConstraints::current());
+    }
+
+    #[test]
+    fn player_constraints_allowed_chars_match_screen_name_validation() {
+        let policy = PlayerConstraints::current().screen_name.allowed_chars;
+        let allows = |c: char| {
+            let code_point = u32::from(c);
+
+            c == ' '
+                || ((policy.allows_control || !c.is_control())
+                    && (policy.allows_non_space_whitespace || !c.is_whitespace())
+                    && !policy
+                        .disallowed_ranges
+                        .iter()
+                        .any(|[first, last]| (*first..=*last).contains(&code_point)))
+        };
+
+        let mismatch =
+            (char::MIN..=char::MAX).find(|c| allows(*c) != PlayerScreenName::is_allowed_char(*c));
+
+        assert_eq!(mismatch, None);
+    }
+
+    #[test]
+    fn screen_name_availability_json_snapshot() {
+        insta::assert_json_snapshot!(&ScreenNameAvailability::unavailable(
+            "player screen name is empty".into()
+        ));
+    }
+
+    #[tokio::test]
+    async fn constraints_handler_returns_current_constraints() -> anyhow::Result<()> {
+        let state = Arc::new(MockAppState::default());
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server.get("/players/constraints").await;
+
+        response.assert_status(StatusCode::OK);
+        let constraints = response.json::<PlayerConstraints>();
+        assert_eq!(constraints.screen_name.max_size, PlayerScreenName::MAX_SIZE);
+        assert_eq!(constraints.screen_name.min_size, PlayerScreenName::MIN_SIZE);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_player_handler_deletes_current_player() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_delete_player()
+            .with(eq(PlayerId::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server.delete("/players/me").await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_player_handler_fails_if_player_is_already_deleted() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        players_service.expect_delete_player().never();
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server.delete("/players/me").await;
+
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(response.json::<ApiError>().id, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn screen_name_available_handler_returns_availability() -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_screen_name_availability()
+            .with(eq("taken name"))
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(ScreenNameAvailability::unavailable(
+                        "screen name is already taken".into(),
+                    ))
+                })
+            });
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .get("/players/screen_name_available")
+            .add_query_param("name", "taken name")
+            .await;
+
+        response.assert_status_ok();
+        response.assert_json(&ScreenNameAvailability::unavailable(
+            "screen name is already taken".into(),
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn notifications_handler_returns_page_of_current_player() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_notifications()
+            .with(
+                eq(PlayerId::test()),
+                eq(NotificationsQuery {
+                    unread_only: true,
+                    before: Some(NotificationId::test()),
+                    limit: Some(10),
+                }),
+            )
+            .returning(|_, _| {
+                Box::pin(async {
+                    Ok(NotificationsPage {
+                        notifications: vec![Notification::test()],
+                        next_before: None,
+                    })
+                })
+            });
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .get("/players/me/notifications")
+            .add_query_param("unread_only", true)
+            .add_query_param("before", NotificationId::test().0)
+            .add_query_param("limit", 10)
+            .await;
+
+        response.assert_status_ok();
+        let page = response.json::<NotificationsPage>();
+        assert_eq!(page.notifications, vec![Notification::test()]);
+        assert_eq!(page.next_before, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mark_notifications_read_handler_marks_notifications_of_current_player()
+    -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_mark_notifications_read()
+            .withf(|player_id, ids| {
+                player_id == &PlayerId::test() && ids == [NotificationId::test()]
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .post("/players/me/notifications/read")
+            .json(&MarkNotificationsReadRequest::test())
+            .await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sign_in_handler_returns_correct_response_when_players_service_succeds()
+    -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_sign_in()
+            .with(eq(SignInRequest::test_google()))
+            .returning(|_| Box::pin(async { Ok(SignedIn::test()) }));
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .post("/players/sign_in")
+            .json(&SignInRequest::test_google())
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        response.assert_json(&SignInResponse::test());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sign_in_handler_returns_correct_response_when_players_service_fails()
+    -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_sign_in()
+            .with(eq(SignInRequest::test_google()))
+            .returning(|_| {
+                Box::pin(async {
+                    Err(PlayersError::IdToken(IdTokenVerifierError::Validation(
+                        ValidationError::MissingKeyId,
+                    )))
+                })
+            });
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .post("/players/sign_in")
+            .json(&SignInRequest::test_google())
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, "players");
+        assert_eq!(error.id, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn public_player_handler_returns_public_player() -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .with(eq(PlayerId::test()))
+            .returning(|_| Box::pin(async { Ok(Player::test_admin()) }));
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .get(&format!("/players/{}", PlayerId::test().0))
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        response.assert_json(&PublicPlayer::from(Player::test()));
+        assert!(!response.text().contains("role"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn public_player_handler_fails_if_player_not_found() -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        for path in [
+            format!("/players/{}", PlayerId::random().0),
+            "/players/not-a-player-id".into(),
+        ] {
+            let response = server.get(&path).await;
+
+            response.assert_status(StatusCode::NOT_FOUND);
+            assert_eq!(
+                response.json::<ApiError>().id,
+                PlayersError::PROFILE_NOT_FOUND.id,
+                "{path}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn players_batch_handler_looks_up_distinct_ids() -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_players_by_ids()
+            .withf(|player_ids| player_ids == [PlayerId::test()])
+            .returning(|_| Box::pin(async { Ok(vec![Player::test()]) }));
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .post("/players/batch")
+            .json(&PlayersBatchRequest {
+                ids: vec![PlayerId::test(), PlayerId::test()],
+            })
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        response.assert_json(&vec![PublicPlayer::from(Player::test())]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn players_batch_handler_fails_if_too_many_ids() -> anyhow::Result<()> {
+        let state = Arc::new(MockAppState::default());
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .post("/players/batch")
+            .json(&PlayersBatchRequest {
+                ids: (0..=PlayersBatchRequest::MAX_IDS)
+                    .map(|_| PlayerId::random())
+                    .collect(),
+            })
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.json::<ApiError>().id,
+            PlayersError::TOO_MANY_PLAYER_IDS.id
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_players_handler_returns_page_to_admins() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service.expect_verify_token().returning(|_| {
+            Box::pin(async {
+                Ok(AuthTokenClaims {
+                    role: PlayerRole::Admin,
+                    ..AuthTokenClaims::test()
+                })
+            })
+        });
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test_admin()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_list_players()
+            .with(eq(PlayersQuery {
+                after: Some(PlayerId::test()),
+                limit: Some(1),
+            }))
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(Page {
+                        items: vec![Player::test()],
+                        next_cursor: Some(PlayerId::test()),
+                    })
+                })
+            });
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .get("/players")
+            .add_query_param("after", PlayerId::test().0)
+            .add_query_param("limit", 1)
+            .await;
+
+        response.assert_status_ok();
+        response.assert_json(&Page {
+            items: vec![Player::test()],
+            next_cursor: Some(PlayerId::test()),
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_players_handler_fails_for_regular_players() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server.get("/players").await;
+
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(response.json::<ApiError>().id, PlayersError::FORBIDDEN.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn players_query_limit_is_clamped() {
+        let limit = |limit| PlayersQuery { after: None, limit }.limit();
+
+        assert_eq!(limit(None), PlayersQuery::DEFAULT_LIMIT);
+        assert_eq!(limit(Some(0)), 1);
+        assert_eq!(limit(Some(-5)), 1);
+        assert_eq!(limit(Some(10)), 10);
+        assert_eq!(limit(Some(100_000)), PlayersQuery::MAX_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn preferences_handler_returns_preferences() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_preferences()
+            .with(eq(PlayerId::test()))
+            .returning(|_| Box::pin(async { Ok(PlayerPreferences::default()) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server.get("/players/me/preferences").await;
+
+        response.assert_status_ok();
+        response.assert_json(&PlayerPreferences::default());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_preferences_handler_stores_preferences() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_set_preferences()
+            .with(eq(PlayerId::test()), eq(PlayerPreferences::test()))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .put("/players/me/preferences")
+            .json(&PlayerPreferences::test())
+            .await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_preferences_handler_rejects_unknown_fields() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service.expect_set_preferences().never();
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .put("/players/me/preferences")
+            .text(r#"{"theme": "Dark", "font_size": 12}"#)
+            .content_type("application/json")
+            .await;
+
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ban_player_handler_bans_player() -> anyhow::Result<()> {
+        let until = DateTime::from_timestamp(4102444800, 0).unwrap();
+        let mut jwt_service = MockJwtService::new();
+        jwt_service.expect_verify_token().returning(|_| {
+            Box::pin(async {
+                Ok(AuthTokenClaims {
+                    role: PlayerRole::Admin,
+                    ..AuthTokenClaims::test()
+                })
+            })
+        });
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test_admin()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_ban_player()
+            .with(eq(PlayerId::test()), eq(Some(until)))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .put(&format!("/players/{}/ban", PlayerId::test().0))
+            .json(&BanRequest { until: Some(until) })
+            .await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_friendship_handler_requests_friendship() -> anyhow::Result<()> {
+        let other_id = Friendship::test().addressee_id;
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_request_friendship()
+            .with(eq(PlayerId::test()), eq(other_id))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .post(&format!("/players/friends/{}", other_id.0))
+            .await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_friendship_handler_fails_if_player_befriends_themselves() -> anyhow::Result<()>
+    {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_request_friendship()
+            .returning(|_, _| Box::pin(async { Err(PlayersError::CannotBefriendSelf) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .post(&format!("/players/friends/{}", PlayerId::test().0))
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.json::<ApiError>().id,
+            PlayersError::CANNOT_BEFRIEND_SELF.id
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn block_player_handler_blocks_player() -> anyhow::Result<()> {
+        let other_id = Friendship::test().addressee_id;
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_block_player()
+            .with(eq(PlayerId::test()), eq(other_id))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .post(&format!("/players/blocks/{}", other_id.0))
+            .await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn presence_handler_returns_presence_of_player() -> anyhow::Result<()> {
+        let mut presence_service = MockPresenceService::new();
+        presence_service
+            .expect_presence()
+            .with(eq(PlayerId::test()))
+            .returning(|_| Box::pin(async { Ok(Presence::Online) }));
+        presence_service
+            .expect_online_count()
+            .returning(|| Box::pin(async { Ok(3) }));
+        let state = Arc::new(MockAppState::default().with_presence_service(presence_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let presence = server
+            .get(&format!("/players/{}/presence", PlayerId::test().0))
+            .await;
+        let online_count = server.get("/players/online_count").await;
+
+        presence.assert_status(StatusCode::OK);
+        presence.assert_json(&Presence::Online);
+        online_count.assert_status(StatusCode::OK);
+        online_count.assert_json(&OnlineCountResponse { online: 3 });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn report_player_handler_creates_report() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_report_player()
+            .with(eq(PlayerId::test()), eq(ReportRequest::test()))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(Report::test()) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .post("/players/reports")
+            .json(&ReportRequest::test())
+            .await;
+
+        response.assert_status(StatusCode::CREATED);
+        response.assert_json(&Report::test());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn report_handlers_list_and_resolve_reports_for_admins() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service.expect_verify_token().returning(|_| {
+            Box::pin(async {
+                Ok(AuthTokenClaims {
+                    role: PlayerRole::Admin,
+                    ..AuthTokenClaims::test()
+                })
+            })
+        });
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test_admin()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_open_reports()
+            .with(eq(ReportsQuery::default()))
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(Page {
+                        items: vec![Report::test()],
+                        next_cursor: None,
+                    })
+                })
+            });
+        players_service
+            .expect_resolve_report()
+            .with(eq(ReportId::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
 
-    #[test]
-    fn sign_in_request_json_snapshot() {
-        insta::assert_json_snapshot!(&SignInRequest::test_google());
-    }
+        let listed = server.get("/admin/reports").await;
+        let resolved = server
+            .post(&format!("/admin/reports/{}/resolve", ReportId::test().0))
+            .await;
+        let unknown = server.post("/admin/reports/not-a-report-id/resolve").await;
 
-    #[test]
-    fn sign_in_response_json_snapshot() {
-        insta::assert_json_snapshot!(&SignInResponse::test());
+        listed.assert_status(StatusCode::OK);
+        listed.assert_json(&Page {
+            items: vec![Report::test()],
+            next_cursor: None,
+        });
+        resolved.assert_status(StatusCode::NO_CONTENT);
+        unknown.assert_status(StatusCode::NOT_FOUND);
+        assert_eq!(
+            unknown.json::<ApiError>().id,
+            PlayersError::REPORT_NOT_FOUND.id
+        );
+
+        Ok(())
     }
 
     #[tokio::test]
-    async fn sign_in_handler_returns_correct_response_when_players_service_succeds()
-    -> anyhow::Result<()> {
+    async fn notifications_handler_touches_last_seen_of_current_player() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
         let mut players_service = MockPlayersService::new();
         players_service
-            .expect_sign_in()
-            .with(eq(SignInRequest::test_google()))
-            .returning(|_| Box::pin(async { Ok(AuthToken::test()) }));
-        let state = Arc::new(MockAppState::default().with_players_service(players_service));
-        let server = TestServer::new(router().with_state(state))?;
+            .expect_player_by_id()
+            .with(eq(PlayerId::test()))
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .with(eq(Player::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service.expect_notifications().returning(|_, _| {
+            Box::pin(async {
+                Ok(NotificationsPage {
+                    notifications: vec![],
+                    next_before: None,
+                })
+            })
+        });
 
-        let response = server
-            .post("/players/sign_in")
-            .json(&SignInRequest::test_google())
-            .await;
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
 
-        response.assert_status(StatusCode::OK);
-        response.assert_json(&SignInResponse::test());
+        let response = server.get("/players/me/notifications").await;
+
+        response.assert_status_ok();
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn sign_in_handler_returns_correct_response_when_players_service_fails()
-    -> anyhow::Result<()> {
+    async fn logout_handler_rejects_banned_player() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        jwt_service.expect_revoke_token().never();
+
         let mut players_service = MockPlayersService::new();
-        players_service
-            .expect_sign_in()
-            .with(eq(SignInRequest::test_google()))
-            .returning(|_| {
-                Box::pin(async {
-                    Err(PlayersError::IdToken(IdTokenVerifierError::Validation(
-                        ValidationError::MissingKeyId,
-                    )))
+        players_service.expect_player_by_id().returning(|_| {
+            Box::pin(async {
+                Ok(Player {
+                    banned_until: Some(DateTime::from_timestamp(4102444800, 0).unwrap()),
+                    ..Player::test()
                 })
-            });
+            })
+        });
+        players_service.expect_touch_last_seen().never();
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server.post("/players/logout").await;
+
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.json::<ApiError>().id,
+            PlayersError::PLAYER_BANNED.id
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refresh_handler_rejects_banned_player() -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service.expect_refresh().returning(|_| {
+            Box::pin(async {
+                Err(PlayersError::PlayerBanned(
+                    DateTime::from_timestamp(4102444800, 0).unwrap(),
+                ))
+            })
+        });
         let state = Arc::new(MockAppState::default().with_players_service(players_service));
         let server = TestServer::new(router().with_state(state))?;
 
         let response = server
-            .post("/players/sign_in")
-            .json(&SignInRequest::test_google())
+            .post("/players/refresh")
+            .json(&RefreshRequest::test())
             .await;
 
-        response.assert_status(StatusCode::BAD_REQUEST);
-        let error = response.json::<ApiError>();
-        assert_eq!(error.module, "players");
-        assert_eq!(error.id, 0);
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.json::<ApiError>().id,
+            PlayersError::PLAYER_BANNED.id
+        );
 
         Ok(())
     }
@@ -183,6 +1947,57 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn player_info_handler_fails_if_auth_token_header_is_malformed() -> anyhow::Result<()> {
+        for header_value in ["valid", "abc", "Bearer", "Bearer ", "Basic dXNlcjpwYXNz"] {
+            let state = Arc::new(MockAppState::default());
+            let mut server = TestServer::new(router().with_state(state))?;
+            server.add_header(AUTHORIZATION, header_value);
+
+            let response = server.get("/players/player_info").await;
+
+            response.assert_status(StatusCode::UNAUTHORIZED);
+            let error = response.json::<ApiError>();
+            assert_eq!(error.module, "players");
+            assert_eq!(error.id, 17, "{header_value}");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn player_info_handler_accepts_case_insensitive_bearer_scheme() -> anyhow::Result<()> {
+        for header_value in ["bearer valid", "BEARER valid"] {
+            let mut jwt_service = MockJwtService::new();
+            jwt_service
+                .expect_verify_token()
+                .withf(|token| token.as_ref() == "valid")
+                .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+            let mut players_service = MockPlayersService::new();
+            players_service
+                .expect_player_by_id()
+                .returning(|_| Box::pin(async { Ok(Player::test()) }));
+            players_service
+                .expect_touch_last_seen()
+                .returning(|_| Box::pin(async { Ok(()) }));
+
+            let state = Arc::new(
+                MockAppState::default()
+                    .with_jwt_service(jwt_service)
+                    .with_players_service(players_service),
+            );
+            let mut server = TestServer::new(router().with_state(state))?;
+            server.add_header(AUTHORIZATION, header_value);
+
+            let response = server.get("/players/player_info").await;
+
+            response.assert_status_ok();
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn player_info_handler_fails_if_auth_token_validation_fails() -> anyhow::Result<()> {
         let mut jwt_service = MockJwtService::new();
@@ -190,9 +2005,11 @@ mod tests {
             .expect_verify_token()
             .withf(|token| token.as_ref() == "invalid")
             .returning(|_| {
-                Err(PlayersError::AuthToken(jsonwebtoken::errors::Error::from(
-                    ErrorKind::InvalidToken,
-                )))
+                Box::pin(async {
+                    Err(PlayersError::AuthToken(jsonwebtoken::errors::Error::from(
+                        ErrorKind::InvalidToken,
+                    )))
+                })
             });
 
         let state = Arc::new(MockAppState::default().with_jwt_service(jwt_service));
@@ -215,7 +2032,7 @@ mod tests {
         jwt_service
             .expect_verify_token()
             .withf(|token| token.as_ref() == "valid")
-            .returning(|_| Ok(AuthTokenClaims::test()));
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
 
         let mut players_service = MockPlayersService::new();
         players_service
@@ -240,4 +2057,240 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn sign_in_methods_handler_lists_sign_in_methods_of_current_player() -> anyhow::Result<()>
+    {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_sign_in_methods()
+            .with(eq(PlayerId::test()))
+            .returning(|_| Box::pin(async { Ok(linked_sign_in_methods()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server.get("/players/sign_in_methods").await;
+
+        response.assert_status_ok();
+        response.assert_json(&linked_sign_in_methods());
+
+        Ok(())
+    }
+
+    #[test]
+    fn linked_sign_in_methods_json_snapshot() {
+        insta::assert_json_snapshot!(&linked_sign_in_methods());
+    }
+
+    fn linked_sign_in_methods() -> Vec<LinkedSignInMethod> {
+        vec![
+            LinkedSignInMethod::ThirdParty {
+                provider: ThirdPartySignInProvider::Google,
+            },
+            LinkedSignInMethod::EmailPassword {
+                email: Email::test(),
+            },
+            LinkedSignInMethod::Anonymous {
+                device_id: DeviceId::test(),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn link_sign_in_method_handler_links_to_current_player() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .withf(|token| token.as_ref() == "valid")
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_link_sign_in_method()
+            .with(
+                eq(PlayerId::test()),
+                eq(LinkSignInMethodRequest::test_google()),
+            )
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .post("/players/sign_in_methods")
+            .json(&LinkSignInMethodRequest::test_google())
+            .await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn link_sign_in_method_handler_fails_if_already_linked() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_service
+            .expect_link_sign_in_method()
+            .returning(|_, _| Box::pin(async { Err(PlayersError::SignInMethodAlreadyLinked) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server
+            .post("/players/sign_in_methods")
+            .json(&LinkSignInMethodRequest::test_google())
+            .await;
+
+        response.assert_status(StatusCode::CONFLICT);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, "players");
+        assert_eq!(error.id, 12);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refresh_handler_returns_new_tokens() -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_refresh()
+            .with(eq(RefreshToken::test()))
+            .returning(|_| Box::pin(async { Ok(TokenPair::test()) }));
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .post("/players/refresh")
+            .json(&RefreshRequest::test())
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        response.assert_json(&RefreshResponse::test());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refresh_handler_fails_on_refresh_token_reuse() -> anyhow::Result<()> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_refresh()
+            .returning(|_| Box::pin(async { Err(PlayersError::RefreshTokenReused) }));
+        let state = Arc::new(MockAppState::default().with_players_service(players_service));
+        let server = TestServer::new(router().with_state(state))?;
+
+        let response = server
+            .post("/players/refresh")
+            .json(&RefreshRequest::test())
+            .await;
+
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, "players");
+        assert_eq!(error.id, 13);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn logout_handler_revokes_current_token() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .withf(|token| token.as_ref() == "valid")
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+        jwt_service
+            .expect_revoke_token()
+            .withf(|claims| claims.jti == AuthTokenClaims::test().jti)
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        let response = server.post("/players/logout").await;
+
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn player_info_handler_fails_if_auth_token_revoked() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Err(PlayersError::AuthTokenRevoked) }));
+
+        let state = Arc::new(MockAppState::default().with_jwt_service(jwt_service));
+        let mut server = TestServer::new(router().with_state(state))?;
+        server.add_header(AUTHORIZATION, "Bearer revoked");
+
+        let response = server.get("/players/player_info").await;
+
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, "players");
+        assert_eq!(error.id, 15);
+
+        Ok(())
+    }
 }
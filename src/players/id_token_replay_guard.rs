@@ -0,0 +1,147 @@
+use crate::config::IdTokenReplayProtectionConfig;
+use crate::players::error::PlayersError;
+use crate::players::http::IdToken;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Remembers already used [IdToken]s for their validity window to reject replays.
+pub struct IdTokenReplayGuard {
+    /// Max number of remembered [IdToken]s.
+    max_entries: usize,
+
+    /// SHA-256 hashes of the used [IdToken]s with their expiration timestamps.
+    used: Mutex<HashMap<[u8; 32], i64>>,
+}
+
+impl IdTokenReplayGuard {
+    /// Creates a new [IdTokenReplayGuard] with the given [IdTokenReplayProtectionConfig].
+    pub fn new(config: IdTokenReplayProtectionConfig) -> IdTokenReplayGuard {
+        IdTokenReplayGuard {
+            max_entries: config.max_entries.max(1),
+            used: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Marks the given [IdToken], expiring at `exp`, as used. Fails with
+    /// [PlayersError::IdTokenReused] if the [IdToken] has already been used.
+    pub fn use_token(&self, id_token: &IdToken, exp: i64) -> Result<(), PlayersError> {
+        let hash = hash(id_token);
+        let now = Utc::now().timestamp();
+
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+
+        if used.get(&hash).is_some_and(|used_exp| *used_exp >= now) {
+            Err(PlayersError::IdTokenReused)?
+        }
+
+        if used.len() >= self.max_entries {
+            used.retain(|_, used_exp| *used_exp >= now);
+        }
+        if used.len() >= self.max_entries {
+            // Still full with valid entries, forget the one expiring first.
+            if let Some(oldest) = used.iter().min_by_key(|(_, exp)| **exp).map(|(k, _)| *k) {
+                used.remove(&oldest);
+            }
+        }
+
+        used.insert(hash, exp);
+
+        Ok(())
+    }
+
+    /// Forgets the given [IdToken] marked as used by [IdTokenReplayGuard::use_token], so that it
+    /// can be used again.
+    pub fn forget_token(&self, id_token: &IdToken) {
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        used.remove(&hash(id_token));
+    }
+}
+
+/// Returns the SHA-256 hash of the given [IdToken].
+fn hash(id_token: &IdToken) -> [u8; 32] {
+    Sha256::digest(id_token.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(max_entries: usize) -> IdTokenReplayGuard {
+        IdTokenReplayGuard::new(IdTokenReplayProtectionConfig {
+            enabled: true,
+            max_entries,
+        })
+    }
+
+    fn exp_in_future() -> i64 {
+        Utc::now().timestamp() + 3600
+    }
+
+    #[test]
+    fn use_token_rejects_replayed_token() {
+        let guard = guard(10);
+
+        assert!(guard.use_token(&IdToken::test(), exp_in_future()).is_ok());
+        assert!(matches!(
+            guard.use_token(&IdToken::test(), exp_in_future()),
+            Err(PlayersError::IdTokenReused)
+        ));
+    }
+
+    #[test]
+    fn forget_token_allows_using_token_again() {
+        let guard = guard(10);
+
+        assert!(guard.use_token(&IdToken::test(), exp_in_future()).is_ok());
+        guard.forget_token(&IdToken::test());
+
+        assert!(guard.use_token(&IdToken::test(), exp_in_future()).is_ok());
+    }
+
+    #[test]
+    fn use_token_accepts_different_tokens() {
+        let guard = guard(10);
+
+        assert!(
+            guard
+                .use_token(&IdToken("a".into()), exp_in_future())
+                .is_ok()
+        );
+        assert!(
+            guard
+                .use_token(&IdToken("b".into()), exp_in_future())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn use_token_forgets_expired_tokens() {
+        let guard = guard(1);
+        let exp_in_past = Utc::now().timestamp() - 10;
+
+        assert!(guard.use_token(&IdToken("a".into()), exp_in_past).is_ok());
+        assert!(
+            guard
+                .use_token(&IdToken("b".into()), exp_in_future())
+                .is_ok()
+        );
+
+        let used = guard.used.lock().unwrap();
+        assert_eq!(used.len(), 1);
+    }
+
+    #[test]
+    fn use_token_stays_within_max_entries() {
+        let guard = guard(2);
+
+        for i in 0..5 {
+            let id_token = IdToken(format!("token-{i}"));
+            assert!(guard.use_token(&id_token, exp_in_future() + i).is_ok());
+        }
+
+        let used = guard.used.lock().unwrap();
+        assert_eq!(used.len(), 2);
+    }
+}
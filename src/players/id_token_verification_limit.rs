@@ -0,0 +1,76 @@
+use crate::config::IdTokenVerificationLimitConfig;
+use crate::players::error::PlayersError;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Limits the number of concurrent third party ID token verifications.
+pub struct IdTokenVerificationLimit {
+    /// Permits for concurrent verifications.
+    permits: Semaphore,
+
+    /// How long to wait for a permit before shedding the verification.
+    acquire_timeout: Duration,
+}
+
+impl IdTokenVerificationLimit {
+    /// Creates a new [IdTokenVerificationLimit] with the given [IdTokenVerificationLimitConfig].
+    pub fn new(config: IdTokenVerificationLimitConfig) -> IdTokenVerificationLimit {
+        IdTokenVerificationLimit {
+            permits: Semaphore::new(config.max_concurrent),
+            acquire_timeout: config.acquire_timeout,
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns a test [IdTokenVerificationLimit] that practically never sheds verifications.
+    pub fn test() -> IdTokenVerificationLimit {
+        IdTokenVerificationLimit::new(IdTokenVerificationLimitConfig {
+            max_concurrent: 1024,
+            acquire_timeout: Duration::from_secs(1),
+        })
+    }
+
+    /// Acquires a verification permit, waiting at most the configured acquire timeout.
+    /// Fails with [PlayersError::IdTokenVerificationOverloaded] if no permit is available in time.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, PlayersError> {
+        match tokio::time::timeout(self.acquire_timeout, self.permits.acquire()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            _ => Err(PlayersError::IdTokenVerificationOverloaded),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(max_concurrent: usize) -> IdTokenVerificationLimit {
+        IdTokenVerificationLimit::new(IdTokenVerificationLimitConfig {
+            max_concurrent,
+            acquire_timeout: Duration::from_millis(10),
+        })
+    }
+
+    #[tokio::test]
+    async fn acquire_sheds_when_all_permits_are_taken() {
+        let limit = limit(1);
+
+        let permit = limit.acquire().await;
+        let shed = limit.acquire().await;
+
+        assert!(permit.is_ok());
+        assert!(matches!(
+            shed,
+            Err(PlayersError::IdTokenVerificationOverloaded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn acquire_succeeds_once_a_permit_is_released() {
+        let limit = limit(1);
+
+        drop(limit.acquire().await);
+
+        assert!(limit.acquire().await.is_ok());
+    }
+}
@@ -1,31 +1,74 @@
 use crate::app_state::AppState;
-use crate::config::JwtConfig;
+use crate::config::{JwtAlgorithm, JwtConfig};
 use crate::players::error::PlayersError;
-use crate::players::player::PlayerId;
+use crate::players::player::{Authenticated, PlayerId, PlayerRole};
+#[cfg(test)]
+use crate::players::players_db::MockPlayersDb;
+use crate::players::players_db::{PlayersDb, SlowQueryLoggingPlayersDb};
+use crate::players::refresh_token::{RefreshToken, RefreshTokenFamilyId};
 use anyhow::Context;
+use axum::RequestPartsExt;
 use axum::extract::FromRequestParts;
-use axum::http::header::AUTHORIZATION;
+use axum::http::HeaderValue;
 use axum::http::request::Parts;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::path::Path;
+use uuid::Uuid;
 
 /// Operations with [Player]s JWT tokens.
 #[cfg_attr(test, mockall::automock)]
 pub trait JwtService {
-    /// Creates an [AuthToken] from the given [PlayerId].
-    fn create_token(&self, player_id: PlayerId) -> Result<AuthToken<'static>, PlayersError>;
+    /// Creates a [TokenPair] of an [AuthToken] and a new [RefreshToken] for the given [PlayerId]
+    /// with the given [PlayerRole], within the given [RefreshTokenFamilyId].
+    fn create_token_pair(
+        &self,
+        player_id: PlayerId,
+        role: PlayerRole,
+        family_id: RefreshTokenFamilyId,
+    ) -> Result<TokenPair, PlayersError>;
 
     /// Verifies the given [AuthToken] and returns its [AuthTokenClaims].
+    /// Fails with [PlayersError::AuthTokenRevoked] if the token has been revoked.
     #[allow(clippy::needless_lifetimes)]
-    fn verify_token<'a>(&self, token: &AuthToken<'a>) -> Result<AuthTokenClaims, PlayersError>;
+    fn verify_token<'a>(
+        &self,
+        token: &AuthToken<'a>,
+    ) -> impl Future<Output = Result<AuthTokenClaims, PlayersError>> + Send;
+
+    /// Revokes the token with the given [AuthTokenClaims] before it expires, along with the
+    /// [RefreshToken]s of its [AuthTokenClaims::fid].
+    fn revoke_token(
+        &self,
+        claims: &AuthTokenClaims,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
 }
 
 /// Auth token representing a result of a successful sign in.
 #[derive(Debug, Clone, Serialize, Deserialize, derive_more::AsRef, PartialEq)]
 pub struct AuthToken<'a>(pub Cow<'a, str>);
 
-impl AuthToken<'_> {
+impl<'a> AuthToken<'a> {
+    /// Extracts an [AuthToken] from an `Authorization: Bearer <token>` header value, where the
+    /// scheme is case-insensitive.
+    pub fn from_authorization_header(
+        header_value: &'a HeaderValue,
+    ) -> Result<AuthToken<'a>, PlayersError> {
+        const SCHEME: &str = "Bearer ";
+
+        let header_value_str = header_value
+            .to_str()
+            .map_err(|_| PlayersError::AuthTokenMalformed)?;
+        match header_value_str.split_at_checked(SCHEME.len()) {
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case(SCHEME) && !token.is_empty() => {
+                Ok(AuthToken(Cow::Borrowed(token)))
+            }
+
+            _ => Err(PlayersError::AuthTokenMalformed),
+        }
+    }
+
     #[cfg(test)]
     /// Returns a test [AuthToken].
     pub fn test() -> AuthToken<'static> {
@@ -33,6 +76,31 @@ impl AuthToken<'_> {
     }
 }
 
+/// [AuthToken] with a [RefreshToken] to obtain a new one once it expires.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenPair {
+    /// Short-lived [AuthToken].
+    pub auth_token: AuthToken<'static>,
+
+    /// Long-lived [RefreshToken].
+    pub refresh_token: RefreshToken,
+
+    /// When the [RefreshToken] expires.
+    pub refresh_token_expires_at: DateTime<Utc>,
+}
+
+impl TokenPair {
+    #[cfg(test)]
+    /// Returns a test [TokenPair].
+    pub fn test() -> TokenPair {
+        TokenPair {
+            auth_token: AuthToken::test(),
+            refresh_token: RefreshToken::test(),
+            refresh_token_expires_at: DateTime::from_timestamp(4102444800, 0).unwrap(),
+        }
+    }
+}
+
 /// [AuthToken] claims shape.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthTokenClaims {
@@ -44,6 +112,23 @@ pub struct AuthTokenClaims {
 
     /// Subject [PlayerId] the token is issued for.
     pub sub: PlayerId,
+
+    /// Unique [AuthTokenId] of the token.
+    pub jti: AuthTokenId,
+
+    /// Who issued the token.
+    pub iss: String,
+
+    /// Who the token is intended for.
+    pub aud: String,
+
+    /// [PlayerRole] of the subject when the token was issued.
+    #[serde(default)]
+    pub role: PlayerRole,
+
+    /// [RefreshTokenFamilyId] of the sign in the token has been issued within, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fid: Option<RefreshTokenFamilyId>,
 }
 
 impl AuthTokenClaims {
@@ -54,33 +139,48 @@ impl AuthTokenClaims {
             exp: 123,
             iat: 456,
             sub: PlayerId::test(),
+            jti: AuthTokenId::test(),
+            iss: "gamehub".into(),
+            aud: "gamehub".into(),
+            role: PlayerRole::Player,
+            fid: Some(RefreshTokenFamilyId::test()),
         }
     }
 }
 
+/// Unique ID of an [AuthToken], used to revoke it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct AuthTokenId(pub Uuid);
+
+impl AuthTokenId {
+    /// Creates a new random [AuthTokenId].
+    pub fn random() -> AuthTokenId {
+        AuthTokenId(Uuid::now_v7())
+    }
+
+    #[cfg(test)]
+    /// Returns a test [AuthTokenId].
+    pub fn test() -> AuthTokenId {
+        AuthTokenId(Uuid::from_u128(1122334455))
+    }
+}
+
 impl<S: AppState> FromRequestParts<S> for AuthTokenClaims {
     type Rejection = PlayersError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        match parts
-            .headers
-            .get(AUTHORIZATION)
-            .and_then(|header_value| header_value.to_str().ok())
-        {
-            Some(header_value_str) => {
-                let jwt_token = AuthToken(Cow::Borrowed(&header_value_str["Bearer ".len()..]));
-                let claims = state.jwt_service().verify_token(&jwt_token)?;
-                Ok(claims)
-            }
-
-            None => Err(PlayersError::AuthTokenMissing),
-        }
+        let Authenticated { claims, .. } = parts.extract_with_state(state).await?;
+        Ok(claims)
     }
 }
 
 /// Default implementation of [JwtService].
 #[derive(Clone)]
-pub struct JwtServiceDefault {
+pub struct JwtServiceDefault<D = SlowQueryLoggingPlayersDb> {
+    /// [PlayersDb] keeping track of revoked tokens.
+    players_db: D,
+
     /// JWT [Validation] settings.
     validation: jsonwebtoken::Validation,
 
@@ -93,43 +193,157 @@ pub struct JwtServiceDefault {
     /// JWT [Header] for JWT creation.
     header: jsonwebtoken::Header,
 
+    /// Issuer of the created tokens.
+    issuer: String,
+
+    /// Audience of the created tokens.
+    audience: String,
+
     /// [Duration] how long the token is valid for.
     token_ttl: Duration,
+
+    /// [Duration] how long the refresh token is valid for.
+    refresh_token_ttl: Duration,
 }
 
-impl JwtServiceDefault {
-    /// Creates a new [JwtServiceDefault] with the given [JwtConfig].
-    pub fn new(config: JwtConfig) -> JwtServiceDefault {
-        let validation = jsonwebtoken::Validation::default();
-        let decoding_key = jsonwebtoken::DecodingKey::from_secret(config.secret.as_ref());
-        let encoding_key = jsonwebtoken::EncodingKey::from_secret(config.secret.as_ref());
-        let header = jsonwebtoken::Header::default();
+impl<D> JwtServiceDefault<D> {
+    /// Creates a new [JwtServiceDefault] with the given [JwtConfig] and [PlayersDb].
+    /// Fails if the keys of the configured [JwtAlgorithm] are missing or invalid.
+    pub fn new(config: JwtConfig, players_db: D) -> anyhow::Result<JwtServiceDefault<D>> {
+        let (algorithm, decoding_key, encoding_key) = match config.algorithm {
+            JwtAlgorithm::Hs256 => {
+                anyhow::ensure!(!config.secret.is_empty(), "JWT secret is missing");
+
+                (
+                    jsonwebtoken::Algorithm::HS256,
+                    jsonwebtoken::DecodingKey::from_secret(config.secret.as_ref()),
+                    jsonwebtoken::EncodingKey::from_secret(config.secret.as_ref()),
+                )
+            }
+            JwtAlgorithm::Rs256 => {
+                let private_key =
+                    read_key(config.private_key_path.as_deref()).context("read JWT private key")?;
+                let public_key =
+                    read_key(config.public_key_path.as_deref()).context("read JWT public key")?;
+
+                (
+                    jsonwebtoken::Algorithm::RS256,
+                    jsonwebtoken::DecodingKey::from_rsa_pem(&public_key)
+                        .context("parse JWT public key")?,
+                    jsonwebtoken::EncodingKey::from_rsa_pem(&private_key)
+                        .context("parse JWT private key")?,
+                )
+            }
+        };
+        let mut validation = jsonwebtoken::Validation::new(algorithm);
+        validation.set_issuer(&[&config.issuer]);
+        validation.set_audience(&[&config.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        let header = jsonwebtoken::Header::new(algorithm);
+        let issuer = config.issuer;
+        let audience = config.audience;
         let token_ttl = config.ttl;
+        let refresh_token_ttl = config.refresh_token_ttl;
 
-        JwtServiceDefault {
+        Ok(JwtServiceDefault {
+            players_db,
             validation,
             decoding_key,
             encoding_key,
             header,
+            issuer,
+            audience,
             token_ttl,
+            refresh_token_ttl,
+        })
+    }
+}
+
+/// Reads a PEM encoded key from the given path.
+fn read_key(path: Option<&Path>) -> anyhow::Result<Vec<u8>> {
+    let path = path.context("key path is missing")?;
+
+    std::fs::read(path).with_context(|| format!("read {}", path.display()))
+}
+
+#[cfg(test)]
+impl JwtServiceDefault<MockPlayersDb> {
+    /// Returns a test [JwtServiceDefault] without revoked tokens.
+    pub fn test() -> JwtServiceDefault<MockPlayersDb> {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_is_token_revoked()
+            .returning(|_| Box::pin(async { Ok(false) }));
+
+        JwtServiceDefault::new(JwtConfig::test(), players_db).unwrap()
+    }
+}
+
+impl<D: PlayersDb + Sync> JwtService for JwtServiceDefault<D> {
+    fn create_token_pair(
+        &self,
+        player_id: PlayerId,
+        role: PlayerRole,
+        family_id: RefreshTokenFamilyId,
+    ) -> Result<TokenPair, PlayersError> {
+        let auth_token = self.create_token(player_id, role, Some(family_id))?;
+
+        Ok(TokenPair {
+            auth_token,
+            refresh_token: RefreshToken::random(),
+            refresh_token_expires_at: Utc::now() + self.refresh_token_ttl,
+        })
+    }
+
+    async fn verify_token(&self, token: &AuthToken<'_>) -> Result<AuthTokenClaims, PlayersError> {
+        let claims: AuthTokenClaims =
+            jsonwebtoken::decode(token.as_ref(), &self.decoding_key, &self.validation)
+                .map_err(PlayersError::AuthToken)?
+                .claims;
+
+        if self.players_db.is_token_revoked(&claims.jti).await? {
+            Err(PlayersError::AuthTokenRevoked)?
         }
+
+        Ok(claims)
     }
 
-    #[cfg(test)]
-    /// Returns a test [JwtServiceDefault].
-    pub fn test() -> JwtServiceDefault {
-        JwtServiceDefault::new(JwtConfig::test())
+    async fn revoke_token(&self, claims: &AuthTokenClaims) -> Result<(), PlayersError> {
+        let expires_at = DateTime::from_timestamp(claims.exp, 0).context("token expiration")?;
+
+        self.players_db
+            .revoke_token(&claims.jti, expires_at)
+            .await?;
+        if let Some(family_id) = &claims.fid {
+            self.players_db
+                .revoke_refresh_token_family(family_id)
+                .await?;
+        }
+
+        Ok(())
     }
 }
 
-impl JwtService for JwtServiceDefault {
-    fn create_token(&self, player_id: PlayerId) -> Result<AuthToken<'static>, PlayersError> {
+impl<D> JwtServiceDefault<D> {
+    /// Creates an [AuthToken] for the given [PlayerId] with the given [PlayerRole] within the
+    /// given [RefreshTokenFamilyId], if any.
+    fn create_token(
+        &self,
+        player_id: PlayerId,
+        role: PlayerRole,
+        fid: Option<RefreshTokenFamilyId>,
+    ) -> Result<AuthToken<'static>, PlayersError> {
         let now = Utc::now();
 
         let claims = AuthTokenClaims {
             exp: (now + self.token_ttl).timestamp(),
             iat: now.timestamp(),
             sub: player_id,
+            jti: AuthTokenId::random(),
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
+            role,
+            fid,
         };
 
         let token = jsonwebtoken::encode(&self.header, &claims, &self.encoding_key)
@@ -137,43 +351,61 @@ impl JwtService for JwtServiceDefault {
 
         Ok(AuthToken(Cow::Owned(token)))
     }
-
-    fn verify_token(&self, token: &AuthToken) -> Result<AuthTokenClaims, PlayersError> {
-        let claims = jsonwebtoken::decode(token.as_ref(), &self.decoding_key, &self.validation)
-            .map_err(PlayersError::AuthToken)?
-            .claims;
-
-        Ok(claims)
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn auth_token_claims_json_snapshot() {
         insta::assert_json_snapshot!(&AuthTokenClaims::test());
     }
 
-    #[test]
-    fn jwt_service_encode_and_decode_succeeds() -> anyhow::Result<()> {
+    #[tokio::test]
+    async fn jwt_service_encode_and_decode_succeeds() -> anyhow::Result<()> {
         let player_id = PlayerId::test();
         let service = JwtServiceDefault::test();
         let ttl = service.token_ttl;
 
-        let token = service.create_token(player_id)?;
-        let claims = service.verify_token(&token)?;
+        let token = service.create_token(player_id, PlayerRole::Admin, None)?;
+        let claims = service.verify_token(&token).await?;
 
         assert_eq!(claims.sub, player_id);
+        assert_eq!(claims.role, PlayerRole::Admin);
         assert!(claims.iat <= Utc::now().timestamp());
         assert_eq!(claims.iat + ttl.num_seconds(), claims.exp);
 
         Ok(())
     }
 
-    #[test]
-    fn jwt_service_decode_rejects_expired_tokens() -> anyhow::Result<()> {
+    #[tokio::test]
+    async fn jwt_service_create_token_pair_creates_verifiable_tokens() -> anyhow::Result<()> {
+        let service = JwtServiceDefault::test();
+
+        let first = service.create_token_pair(
+            PlayerId::test(),
+            PlayerRole::Player,
+            RefreshTokenFamilyId::test(),
+        )?;
+        let second = service.create_token_pair(
+            PlayerId::test(),
+            PlayerRole::Player,
+            RefreshTokenFamilyId::random(),
+        )?;
+
+        let claims = service.verify_token(&first.auth_token).await?;
+        assert_eq!(claims.sub, PlayerId::test());
+        assert_eq!(claims.fid, Some(RefreshTokenFamilyId::test()));
+        assert_ne!(first.refresh_token, second.refresh_token);
+        assert!(first.refresh_token_expires_at > Utc::now() + service.token_ttl);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn jwt_service_decode_rejects_expired_tokens() -> anyhow::Result<()> {
         let service = JwtServiceDefault::test();
         let now = Utc::now();
         let exp_in_past = now.timestamp() - service.validation.leeway as i64 - 10;
@@ -185,13 +417,18 @@ mod tests {
                     exp: exp_in_past,
                     iat: iat_in_past,
                     sub: PlayerId::test(),
+                    jti: AuthTokenId::random(),
+                    iss: service.issuer.clone(),
+                    aud: service.audience.clone(),
+                    role: PlayerRole::Player,
+                    fid: None,
                 },
                 &service.encoding_key,
             )?
             .into(),
         );
 
-        let result = service.verify_token(&claims_expired);
+        let result = service.verify_token(&claims_expired).await;
 
         assert!(
             matches!(result, Err(PlayersError::AuthToken(e)) if *e.kind() == jsonwebtoken::errors::ErrorKind::ExpiredSignature)
@@ -200,23 +437,185 @@ mod tests {
         Ok(())
     }
 
-    #[test]
-    fn jwt_service_decode_rejects_wrong_signatures() -> anyhow::Result<()> {
+    #[tokio::test]
+    async fn jwt_service_decode_rejects_wrong_signatures() -> anyhow::Result<()> {
         let service = JwtServiceDefault::test();
-        let service_with_different_secret = {
-            let mut service = service.clone();
-            service.encoding_key =
-                jsonwebtoken::EncodingKey::from_secret("wrong-secret".as_bytes());
-            service
+        let service_with_different_secret = JwtServiceDefault {
+            encoding_key: jsonwebtoken::EncodingKey::from_secret("wrong-secret".as_bytes()),
+            ..JwtServiceDefault::test()
         };
 
-        let claims_wrong_signature = service_with_different_secret.create_token(PlayerId::test())?;
+        let claims_wrong_signature = service_with_different_secret.create_token(
+            PlayerId::test(),
+            PlayerRole::Player,
+            None,
+        )?;
 
-        let result = service.verify_token(&claims_wrong_signature);
+        let result = service.verify_token(&claims_wrong_signature).await;
         assert!(
             matches!(result, Err(PlayersError::AuthToken(e)) if *e.kind() == jsonwebtoken::errors::ErrorKind::InvalidSignature)
         );
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn jwt_service_verify_rejects_revoked_tokens() -> anyhow::Result<()> {
+        let revoked = Arc::new(Mutex::new(Vec::new()));
+        let mut players_db = MockPlayersDb::new();
+        players_db.expect_revoke_token().returning({
+            let revoked = revoked.clone();
+            move |jti, _| {
+                revoked.lock().unwrap().push(*jti);
+                Box::pin(async { Ok(()) })
+            }
+        });
+        players_db.expect_is_token_revoked().returning({
+            let revoked = revoked.clone();
+            move |jti| {
+                let is_revoked = revoked.lock().unwrap().contains(jti);
+                Box::pin(async move { Ok(is_revoked) })
+            }
+        });
+        let service = JwtServiceDefault::new(JwtConfig::test(), players_db)?;
+        let token = service.create_token(PlayerId::test(), PlayerRole::Player, None)?;
+        let other_token = service.create_token(PlayerId::test(), PlayerRole::Player, None)?;
+
+        let claims = service.verify_token(&token).await?;
+        service.revoke_token(&claims).await?;
+
+        let result = service.verify_token(&token).await;
+
+        assert!(matches!(result, Err(PlayersError::AuthTokenRevoked)));
+        assert!(service.verify_token(&other_token).await.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn jwt_service_revoke_token_revokes_refresh_token_family() -> anyhow::Result<()> {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_revoke_token()
+            .times(2)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        players_db
+            .expect_revoke_refresh_token_family()
+            .with(mockall::predicate::eq(RefreshTokenFamilyId::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let service = JwtServiceDefault::new(JwtConfig::test(), players_db)?;
+
+        service.revoke_token(&AuthTokenClaims::test()).await?;
+        service
+            .revoke_token(&AuthTokenClaims {
+                fid: None,
+                ..AuthTokenClaims::test()
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    fn rs256_config() -> JwtConfig {
+        let testdata = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/players/testdata");
+
+        JwtConfig {
+            algorithm: JwtAlgorithm::Rs256,
+            secret: String::new(),
+            private_key_path: Some(testdata.join("jwt_rs256_private.pem")),
+            public_key_path: Some(testdata.join("jwt_rs256_public.pem")),
+            ..JwtConfig::test()
+        }
+    }
+
+    #[tokio::test]
+    async fn jwt_service_rs256_encode_and_decode_succeeds() -> anyhow::Result<()> {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_is_token_revoked()
+            .returning(|_| Box::pin(async { Ok(false) }));
+        let service = JwtServiceDefault::new(rs256_config(), players_db)?;
+
+        let token = service.create_token(PlayerId::test(), PlayerRole::Player, None)?;
+        let header = jsonwebtoken::decode_header(token.as_ref())?;
+        let claims = service.verify_token(&token).await?;
+
+        assert_eq!(header.alg, jsonwebtoken::Algorithm::RS256);
+        assert_eq!(claims.sub, PlayerId::test());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn jwt_service_rs256_rejects_hs256_tokens() -> anyhow::Result<()> {
+        let service = JwtServiceDefault::new(rs256_config(), MockPlayersDb::new())?;
+
+        let hs256_token =
+            JwtServiceDefault::test().create_token(PlayerId::test(), PlayerRole::Player, None)?;
+
+        let result = service.verify_token(&hs256_token).await;
+
+        assert!(
+            matches!(result, Err(PlayersError::AuthToken(e)) if *e.kind() == jsonwebtoken::errors::ErrorKind::InvalidAlgorithm)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn jwt_service_new_fails_without_keys() {
+        let missing_keys = JwtConfig {
+            private_key_path: None,
+            ..rs256_config()
+        };
+        let missing_secret = JwtConfig {
+            secret: String::new(),
+            ..JwtConfig::test()
+        };
+
+        assert!(JwtServiceDefault::new(missing_keys, MockPlayersDb::new()).is_err());
+        assert!(JwtServiceDefault::new(missing_secret, MockPlayersDb::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn jwt_service_decode_rejects_wrong_audience_and_issuer() -> anyhow::Result<()> {
+        let service = JwtServiceDefault::test();
+        let token_with = |iss: &str, aud: &str| -> anyhow::Result<AuthToken<'static>> {
+            let now = Utc::now();
+            let claims = AuthTokenClaims {
+                exp: (now + service.token_ttl).timestamp(),
+                iat: now.timestamp(),
+                sub: PlayerId::test(),
+                jti: AuthTokenId::random(),
+                iss: iss.into(),
+                aud: aud.into(),
+                role: PlayerRole::Player,
+                fid: None,
+            };
+
+            Ok(AuthToken(
+                jsonwebtoken::encode(&service.header, &claims, &service.encoding_key)?.into(),
+            ))
+        };
+
+        let wrong_aud = service
+            .verify_token(&token_with(&service.issuer, "google_client_id")?)
+            .await;
+        let wrong_iss = service
+            .verify_token(&token_with(
+                "https://accounts.google.com",
+                &service.audience,
+            )?)
+            .await;
+
+        assert!(
+            matches!(wrong_aud, Err(PlayersError::AuthToken(e)) if *e.kind() == jsonwebtoken::errors::ErrorKind::InvalidAudience)
+        );
+        assert!(
+            matches!(wrong_iss, Err(PlayersError::AuthToken(e)) if *e.kind() == jsonwebtoken::errors::ErrorKind::InvalidIssuer)
+        );
+
+        Ok(())
+    }
 }
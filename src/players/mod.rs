@@ -1,7 +1,18 @@
 mod error;
+mod friendship;
 pub(crate) mod http;
+mod id_token_replay_guard;
+mod id_token_verification_limit;
 pub mod jwt_service;
+mod notification;
+mod page;
+mod password;
 mod player;
-mod players_db;
+pub(crate) mod players_db;
 pub mod players_service;
+mod preferences;
+pub mod presence;
+mod refresh_token;
+mod report;
 mod sign_in_method;
+pub mod sign_in_rate_limiter;
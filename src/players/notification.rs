@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// ID of a [Notification].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct NotificationId(pub Uuid);
+
+impl NotificationId {
+    /// Generates a new random [NotificationId]. [NotificationId]s are time ordered, newer ones
+    /// are greater.
+    pub fn random() -> NotificationId {
+        NotificationId(Uuid::now_v7())
+    }
+
+    #[cfg(test)]
+    /// Returns a test [NotificationId].
+    pub fn test() -> NotificationId {
+        NotificationId(Uuid::from_u128(5544332211))
+    }
+}
+
+/// What a [Notification] is about.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "notification_kind")]
+pub enum NotificationKind {
+    /// A match has been found for the player.
+    MatchFound,
+
+    /// A game of the player has been resumed.
+    GameResumed,
+
+    /// The player has been challenged by another player.
+    Challenged,
+
+    /// Another player has sent a friend request to the player.
+    FriendRequested,
+
+    /// A friend request sent by the player has been accepted.
+    FriendRequestAccepted,
+}
+
+/// Asynchronous notification in a player's inbox.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Notification {
+    /// [NotificationId] of the notification.
+    pub id: NotificationId,
+
+    /// [NotificationKind] of the notification.
+    pub kind: NotificationKind,
+
+    /// Human readable message of the notification.
+    pub message: String,
+
+    /// When the notification was enqueued.
+    pub created_at: DateTime<Utc>,
+
+    /// Whether the player has read the notification.
+    pub read: bool,
+}
+
+impl Notification {
+    /// Creates a new unread [Notification] of the given [NotificationKind] with the given message.
+    pub fn new(kind: NotificationKind, message: String) -> Notification {
+        Notification {
+            id: NotificationId::random(),
+            kind,
+            message,
+            created_at: Utc::now(),
+            read: false,
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns a test [Notification].
+    pub fn test() -> Notification {
+        Notification {
+            id: NotificationId::test(),
+            kind: NotificationKind::Challenged,
+            message: "You were challenged".into(),
+            created_at: DateTime::from_timestamp(1735689600, 0).unwrap(),
+            read: false,
+        }
+    }
+}
+
+/// Page of [Notification]s, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NotificationsPage {
+    /// [Notification]s of the page.
+    pub notifications: Vec<Notification>,
+
+    /// [NotificationId] to request the next page before, if there is one.
+    pub next_before: Option<NotificationId>,
+}
+
+impl NotificationsPage {
+    /// Number of [Notification]s in a page if not requested otherwise.
+    pub const DEFAULT_LIMIT: i64 = 20;
+
+    /// Max number of [Notification]s in a page.
+    pub const MAX_LIMIT: i64 = 100;
+
+    /// Creates a [NotificationsPage] of at most `limit` [Notification]s out of the given ones,
+    /// fetched with one extra [Notification] to tell whether there is a next page.
+    pub fn new(mut notifications: Vec<Notification>, limit: i64) -> NotificationsPage {
+        let has_next = notifications.len() as i64 > limit;
+        notifications.truncate(limit as usize);
+        let next_before = has_next
+            .then(|| notifications.last().map(|notification| notification.id))
+            .flatten();
+
+        NotificationsPage {
+            notifications,
+            next_before,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notification_json_snapshot() {
+        insta::assert_json_snapshot!(&Notification::test());
+    }
+
+    #[test]
+    fn notifications_page_points_to_next_page_only_if_there_is_one() {
+        let notifications = (0..3)
+            .map(|_| Notification::new(NotificationKind::MatchFound, "Match found".into()))
+            .collect::<Vec<_>>();
+
+        let full = NotificationsPage::new(notifications.clone(), 2);
+        let last = NotificationsPage::new(notifications.clone(), 3);
+
+        assert_eq!(full.notifications, notifications[..2]);
+        assert_eq!(full.next_before, Some(notifications[1].id));
+        assert_eq!(last.notifications, notifications);
+        assert_eq!(last.next_before, None);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::players::player::{Player, PlayerId};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Item of a [Page], identified by a cursor to request the items after it with.
+pub trait PageItem {
+    /// Type of the cursor, e.g. an ID ordered the same way as the items.
+    type Cursor: Serialize + DeserializeOwned;
+
+    /// Returns the cursor of this item.
+    fn cursor(&self) -> Self::Cursor;
+}
+
+impl PageItem for Player {
+    type Cursor = PlayerId;
+
+    fn cursor(&self) -> PlayerId {
+        self.id
+    }
+}
+
+/// Page of [PageItem]s of a cursor-based listing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Page<T: PageItem> {
+    /// [PageItem]s of the page.
+    pub items: Vec<T>,
+
+    /// Cursor to request the next page after, if there is one.
+    pub next_cursor: Option<T::Cursor>,
+}
+
+impl<T: PageItem> Page<T> {
+    /// Creates a [Page] of at most `limit` [PageItem]s out of the given ones, fetched with one
+    /// extra [PageItem] to tell whether there is a next page.
+    pub fn new(mut items: Vec<T>, limit: usize) -> Page<T> {
+        let has_next = items.len() > limit;
+        items.truncate(limit);
+        let next_cursor = has_next
+            .then(|| items.last().map(PageItem::cursor))
+            .flatten();
+
+        Page { items, next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_points_to_next_page_only_if_there_is_one() {
+        let players = (0..3).map(|_| Player::random()).collect::<Vec<_>>();
+
+        let full = Page::new(players.clone(), 2);
+        let last = Page::new(players.clone(), 3);
+
+        assert_eq!(full.items, players[..2]);
+        assert_eq!(full.next_cursor, Some(players[1].id));
+        assert_eq!(last.items, players);
+        assert_eq!(last.next_cursor, None);
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Context;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Argon2, PasswordHash as Argon2PasswordHash};
+use serde::*;
+use std::sync::LazyLock;
+
+/// Plain text password provided by a player. Never appears in [Debug] output.
+#[derive(derive_more::Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[debug("<password_redacted>")]
+pub struct Password(pub String);
+
+impl Password {
+    /// The minimum size of a [Password] in characters.
+    pub const MIN_SIZE: usize = 8;
+
+    /// The maximum size of a [Password] in characters.
+    pub const MAX_SIZE: usize = 128;
+
+    /// Validates this [Password] against [Password::MIN_SIZE] and [Password::MAX_SIZE].
+    pub fn validate(&self) -> Result<(), InvalidPassword> {
+        let size = self.0.chars().count();
+
+        if size < Password::MIN_SIZE {
+            Err(InvalidPassword::TooShort)?
+        }
+        if size > Password::MAX_SIZE {
+            Err(InvalidPassword::TooLong)?
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    /// Returns a test [Password].
+    pub fn test() -> Password {
+        Password("test-password".into())
+    }
+}
+
+/// [Password] validation error.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, thiserror::Error)]
+pub enum InvalidPassword {
+    /// [Password] is smaller than [Password::MIN_SIZE].
+    #[error("password is shorter than {} characters", Password::MIN_SIZE)]
+    TooShort,
+
+    /// [Password] exceeds [Password::MAX_SIZE].
+    #[error("password is longer than {} characters", Password::MAX_SIZE)]
+    TooLong,
+}
+
+/// Argon2 hash of a [Password] in the PHC string format. Never appears in [Debug] output.
+#[derive(derive_more::Debug, Clone, PartialEq, sqlx::Type)]
+#[debug("<password_hash_redacted>")]
+#[sqlx(transparent)]
+pub struct PasswordHash(pub String);
+
+impl PasswordHash {
+    /// Hashes the given [Password] with a random salt.
+    pub fn new(password: &Password) -> anyhow::Result<PasswordHash> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = Argon2::default()
+            .hash_password(password.0.as_bytes(), &salt)
+            .map_err(|e| anyhow::anyhow!("{e}"))
+            .context("hash password")?;
+
+        Ok(PasswordHash(hash.to_string()))
+    }
+
+    /// Whether the given [Password] matches this [PasswordHash].
+    pub fn verify(&self, password: &Password) -> bool {
+        Argon2PasswordHash::new(&self.0).is_ok_and(|hash| {
+            Argon2::default()
+                .verify_password(password.0.as_bytes(), &hash)
+                .is_ok()
+        })
+    }
+
+    /// A [PasswordHash] of a random password, used to keep the time of failed sign-ins for
+    /// unknown emails the same as for known ones.
+    pub fn dummy() -> &'static PasswordHash {
+        static DUMMY: LazyLock<PasswordHash> = LazyLock::new(|| {
+            PasswordHash::new(&Password(uuid::Uuid::now_v7().to_string()))
+                .unwrap_or_else(|_| PasswordHash(String::new()))
+        });
+
+        &DUMMY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_hash_verifies_matching_password() -> anyhow::Result<()> {
+        let hash = PasswordHash::new(&Password::test())?;
+
+        assert!(hash.verify(&Password::test()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn password_hash_rejects_wrong_password() -> anyhow::Result<()> {
+        let hash = PasswordHash::new(&Password::test())?;
+
+        assert!(!hash.verify(&Password("wrong-password".into())));
+        assert!(!PasswordHash::dummy().verify(&Password::test()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn password_hash_uses_random_salt() -> anyhow::Result<()> {
+        assert_ne!(
+            PasswordHash::new(&Password::test())?,
+            PasswordHash::new(&Password::test())?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn password_validate_checks_size() {
+        assert_eq!(
+            Password("p".repeat(Password::MIN_SIZE - 1)).validate(),
+            Err(InvalidPassword::TooShort)
+        );
+        assert_eq!(Password("p".repeat(Password::MIN_SIZE)).validate(), Ok(()));
+        assert_eq!(Password("п".repeat(Password::MAX_SIZE)).validate(), Ok(()));
+        assert_eq!(
+            Password("p".repeat(Password::MAX_SIZE + 1)).validate(),
+            Err(InvalidPassword::TooLong)
+        );
+    }
+
+    #[test]
+    fn password_and_hash_debug_do_not_leak_secrets() -> anyhow::Result<()> {
+        let password = Password("1q2w3e4r_password".into());
+        let hash = PasswordHash::new(&password)?;
+
+        let debug = format!("{password:?} {hash:?}");
+
+        assert!(!debug.contains("1q2w3e4r_password"));
+        assert!(!debug.contains(&hash.0));
+        assert!(debug.contains("<password_redacted>"));
+        assert!(debug.contains("<password_hash_redacted>"));
+
+        Ok(())
+    }
+}
@@ -1,14 +1,16 @@
 use crate::app_state::AppState;
 use crate::players::error::PlayersError;
-use crate::players::jwt_service::AuthTokenClaims;
+use crate::players::jwt_service::{AuthToken, AuthTokenClaims, JwtService};
 use crate::players::players_service::PlayersService;
 use axum::RequestPartsExt;
 use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
 use axum::http::request::Parts;
 use chrono::{DateTime, Utc};
 use petname::{Generator, Petnames};
 use serde::*;
 use std::str::FromStr;
+use tracing::warn;
 use uuid::Uuid;
 
 /// Player representation in the application.
@@ -22,9 +24,38 @@ pub struct Player {
 
     /// When a [Player] has joined.
     pub joined_at: PlayerJoinedAt,
+
+    /// [Player]'s role.
+    #[serde(default)]
+    pub role: PlayerRole,
+
+    /// When a [Player] has last made an authenticated request, if ever.
+    #[serde(default)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+
+    /// Until when a [Player] is banned, if they have ever been.
+    #[serde(default)]
+    pub banned_until: Option<DateTime<Utc>>,
 }
 
 impl Player {
+    /// Returns a new [Player] joining now with a random [PlayerId] and [PlayerScreenName].
+    pub fn random() -> Player {
+        Player {
+            id: PlayerId::random(),
+            screen_name: PlayerScreenName::random(),
+            joined_at: PlayerJoinedAt::now(),
+            role: PlayerRole::Player,
+            last_seen_at: None,
+            banned_until: None,
+        }
+    }
+
+    /// Returns until when this [Player] is banned if the ban is still in effect.
+    pub fn active_ban(&self) -> Option<DateTime<Utc>> {
+        self.banned_until.filter(|until| *until > Utc::now())
+    }
+
     #[cfg(test)]
     /// Returns a test [Player].
     pub fn test() -> Player {
@@ -32,6 +63,18 @@ impl Player {
             id: PlayerId::test(),
             screen_name: PlayerScreenName::test(),
             joined_at: PlayerJoinedAt::test(),
+            role: PlayerRole::Player,
+            last_seen_at: None,
+            banned_until: None,
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns a test [Player] with [PlayerRole::Admin].
+    pub fn test_admin() -> Player {
+        Player {
+            role: PlayerRole::Admin,
+            ..Player::test()
         }
     }
 }
@@ -39,14 +82,133 @@ impl Player {
 impl<S: AppState> FromRequestParts<S> for Player {
     type Rejection = PlayersError;
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        let claims: AuthTokenClaims = parts.extract_with_state(state).await?;
-        let player = state.players_service().player_by_id(&claims.sub).await?;
+        let Authenticated { player, .. } = parts.extract_with_state(state).await?;
         Ok(player)
     }
 }
 
+/// [AuthTokenClaims] of a request along with the [Player] making it. Every authenticated request
+/// goes through this extraction, which rejects banned [Player]s and records when they were last
+/// seen. The result is cached in the request extensions, so it runs once per request.
+#[derive(Debug, Clone)]
+pub struct Authenticated {
+    /// Verified [AuthTokenClaims] of the request.
+    pub claims: AuthTokenClaims,
+
+    /// [Player] the [AuthTokenClaims] belong to.
+    pub player: Player,
+}
+
+impl<S: AppState> FromRequestParts<S> for Authenticated {
+    type Rejection = PlayersError;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(authenticated) = parts.extensions.get::<Authenticated>() {
+            return Ok(authenticated.clone());
+        }
+
+        let header_value = parts
+            .headers
+            .get(AUTHORIZATION)
+            .ok_or(PlayersError::AuthTokenMissing)?;
+        let jwt_token = AuthToken::from_authorization_header(header_value)?;
+        let claims = state.jwt_service().verify_token(&jwt_token).await?;
+
+        let player = state.players_service().player_by_id(&claims.sub).await?;
+        if let Some(until) = player.active_ban() {
+            Err(PlayersError::PlayerBanned(until))?
+        }
+        if let Err(e) = state.players_service().touch_last_seen(&player).await {
+            warn!("Failed to touch last seen of player {:?}: {e}", player.id);
+        }
+
+        let authenticated = Authenticated { claims, player };
+        parts.extensions.insert(authenticated.clone());
+        Ok(authenticated)
+    }
+}
+
+/// Publicly visible part of a [Player], e.g. to show an opponent's profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PublicPlayer {
+    /// [Player]'s ID.
+    pub id: PlayerId,
+
+    /// [Player]'s screen name.
+    pub screen_name: PlayerScreenName,
+
+    /// When a [Player] has joined.
+    pub joined_at: PlayerJoinedAt,
+
+    /// When a [Player] has last made an authenticated request, if ever.
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+impl From<Player> for PublicPlayer {
+    fn from(player: Player) -> PublicPlayer {
+        PublicPlayer {
+            id: player.id,
+            screen_name: player.screen_name,
+            joined_at: player.joined_at,
+            last_seen_at: player.last_seen_at,
+        }
+    }
+}
+
+/// [Player] authenticated if the request has an `Authorization` header, [None] for anonymous
+/// requests. An invalid `Authorization` header still fails the extraction.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(
+    dead_code,
+    reason = "no endpoints serve anonymous and authenticated players yet"
+)]
+pub struct OptionalPlayer(pub Option<Player>);
+
+impl<S: AppState> FromRequestParts<S> for OptionalPlayer {
+    type Rejection = PlayersError;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if !parts.headers.contains_key(AUTHORIZATION) {
+            return Ok(OptionalPlayer(None));
+        }
+
+        let player: Player = parts.extract_with_state(state).await?;
+        Ok(OptionalPlayer(Some(player)))
+    }
+}
+
+/// Authenticated [Player] with [PlayerRole::Admin], extraction fails with
+/// [PlayersError::Forbidden] for other players.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminPlayer(pub Player);
+
+impl<S: AppState> FromRequestParts<S> for AdminPlayer {
+    type Rejection = PlayersError;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Authenticated { claims, player } = parts.extract_with_state(state).await?;
+
+        // The role may have been taken away since the token was issued.
+        if claims.role != PlayerRole::Admin || player.role != PlayerRole::Admin {
+            Err(PlayersError::Forbidden)?
+        }
+
+        Ok(AdminPlayer(player))
+    }
+}
+
+/// Authorization tier of a [Player].
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "player_role")]
+pub enum PlayerRole {
+    /// Regular player.
+    #[default]
+    Player,
+
+    /// Player allowed to use the admin endpoints.
+    Admin,
+}
+
 /// [Player]'s ID.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, sqlx::Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, sqlx::Type)]
+#[sqlx(transparent)]
 pub struct PlayerId(pub Uuid);
 
 impl PlayerId {
@@ -62,8 +224,17 @@ impl PlayerId {
     }
 }
 
+impl FromStr for PlayerId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(PlayerId(Uuid::from_str(s)?))
+    }
+}
+
 /// [Player]'s screen name.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
 pub struct PlayerScreenName(String);
 
 impl PlayerScreenName {
@@ -82,23 +253,56 @@ impl PlayerScreenName {
         PlayerScreenName("test-screen-name".into())
     }
 
-    /// The maximum size of a [PlayerScreenName] in bytes.
+    /// The minimum size of a [PlayerScreenName] in characters.
+    pub const MIN_SIZE: usize = 1;
+
+    /// The maximum size of a [PlayerScreenName] in characters.
     pub const MAX_SIZE: usize = 30;
+
+    /// Inclusive ranges of the invisible format characters (zero-width and bidirectional ones)
+    /// not allowed in a [PlayerScreenName].
+    pub const DISALLOWED_FORMAT_CHARS: [(char, char); 8] = [
+        ('\u{00AD}', '\u{00AD}'),
+        ('\u{061C}', '\u{061C}'),
+        ('\u{180E}', '\u{180E}'),
+        ('\u{200B}', '\u{200F}'),
+        ('\u{202A}', '\u{202E}'),
+        ('\u{2060}', '\u{206F}'),
+        ('\u{FEFF}', '\u{FEFF}'),
+        ('\u{FFF9}', '\u{FFFB}'),
+    ];
+
+    /// Whether the given character is allowed in a [PlayerScreenName]. Control characters,
+    /// whitespace other than a space and [PlayerScreenName::DISALLOWED_FORMAT_CHARS] break the
+    /// UI and allow impersonation, so they are not allowed.
+    pub fn is_allowed_char(c: char) -> bool {
+        let is_format = PlayerScreenName::DISALLOWED_FORMAT_CHARS
+            .iter()
+            .any(|(first, last)| (*first..=*last).contains(&c));
+
+        c == ' ' || !(c.is_control() || c.is_whitespace() || is_format)
+    }
 }
 
 /// [Player]'s screen name validation error.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, thiserror::Error)]
 pub enum InvalidPlayerScreenName {
-    /// [PlayerScreenName] is empty.
+    /// [PlayerScreenName] is empty, i.e. smaller than [PlayerScreenName::MIN_SIZE].
     #[error("player screen name is empty")]
     Empty,
 
     /// [PlayerScreenName] exceeds [PlayerScreenName::MAX_SIZE].
     #[error(
-        "player screen name is larger than allowed max size of {} bytes",
+        "player screen name is longer than allowed max size of {} characters",
         PlayerScreenName::MAX_SIZE
     )]
     ExceedsMaxSize,
+
+    /// [PlayerScreenName] contains control, format or whitespace characters other than a space,
+    /// see [PlayerScreenName::is_allowed_char]. Clients learn about them from the
+    /// `allowed_chars` of `GET /players/constraints`.
+    #[error("player screen name contains invalid characters")]
+    InvalidCharacters,
 }
 
 impl FromStr for PlayerScreenName {
@@ -106,13 +310,17 @@ impl FromStr for PlayerScreenName {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
+        let size = s.chars().count();
 
-        if s.is_empty() {
+        if size < PlayerScreenName::MIN_SIZE {
             Err(InvalidPlayerScreenName::Empty)?
         }
-        if s.len() > PlayerScreenName::MAX_SIZE {
+        if size > PlayerScreenName::MAX_SIZE {
             Err(InvalidPlayerScreenName::ExceedsMaxSize)?
         }
+        if !s.chars().all(PlayerScreenName::is_allowed_char) {
+            Err(InvalidPlayerScreenName::InvalidCharacters)?
+        }
 
         Ok(PlayerScreenName(s.into()))
     }
@@ -120,6 +328,7 @@ impl FromStr for PlayerScreenName {
 
 /// When the [Player] joined.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(transparent)]
 pub struct PlayerJoinedAt(pub DateTime<Utc>);
 
 impl PlayerJoinedAt {
@@ -138,12 +347,298 @@ impl PlayerJoinedAt {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api_error::ApiError;
+    use crate::app_state::MockAppState;
+    use crate::players::jwt_service::MockJwtService;
+    use crate::players::players_service::MockPlayersService;
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum_test::TestServer;
+    use std::sync::Arc;
+
+    fn admin_server(claims_role: PlayerRole, player: Player) -> anyhow::Result<TestServer> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service.expect_verify_token().returning(move |_| {
+            Box::pin(std::future::ready(Ok(AuthTokenClaims {
+                role: claims_role,
+                ..AuthTokenClaims::test()
+            })))
+        });
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(move |_| Box::pin(std::future::ready(Ok(player.clone()))));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let router = Router::new()
+            .route(
+                "/admin",
+                get(|AdminPlayer(player): AdminPlayer| async move { player.screen_name.0 }),
+            )
+            .with_state(state);
+
+        let mut server = TestServer::new(router)?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        Ok(server)
+    }
+
+    fn optional_player_server(jwt_service: MockJwtService) -> anyhow::Result<TestServer> {
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(|_| Box::pin(std::future::ready(Ok(Player::test()))));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let router = Router::new()
+            .route(
+                "/optional",
+                get(|OptionalPlayer(player): OptionalPlayer| async move {
+                    player.map_or("anonymous".to_string(), |player| player.screen_name.0)
+                }),
+            )
+            .with_state(state);
+
+        TestServer::new(router)
+    }
+
+    #[tokio::test]
+    async fn optional_player_is_none_without_auth_token() -> anyhow::Result<()> {
+        let server = optional_player_server(MockJwtService::new())?;
+
+        let response = server.get("/optional").await;
+
+        response.assert_status_ok();
+        response.assert_text("anonymous");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn optional_player_is_some_with_valid_auth_token() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(std::future::ready(Ok(AuthTokenClaims::test()))));
+        let server = optional_player_server(jwt_service)?;
+
+        let response = server
+            .get("/optional")
+            .add_header(AUTHORIZATION, "Bearer valid")
+            .await;
+
+        response.assert_status_ok();
+        response.assert_text(Player::test().screen_name.0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn optional_player_fails_with_invalid_auth_token() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(std::future::ready(Err(PlayersError::AuthTokenRevoked))));
+        let server = optional_player_server(jwt_service)?;
+
+        for header_value in ["Bearer invalid", "Basic dXNlcjpwYXNz"] {
+            let response = server
+                .get("/optional")
+                .add_header(AUTHORIZATION, header_value)
+                .await;
+
+            response.assert_status(StatusCode::UNAUTHORIZED);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn admin_player_accepts_admins() -> anyhow::Result<()> {
+        let server = admin_server(PlayerRole::Admin, Player::test_admin())?;
+
+        let response = server.get("/admin").await;
+
+        response.assert_status_ok();
+        response.assert_text(Player::test_admin().screen_name.0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn admin_player_rejects_regular_players() -> anyhow::Result<()> {
+        for (claims_role, player) in [
+            (PlayerRole::Player, Player::test()),
+            (PlayerRole::Admin, Player::test()),
+        ] {
+            let server = admin_server(claims_role, player)?;
+
+            let response = server.get("/admin").await;
+
+            response.assert_status(StatusCode::FORBIDDEN);
+            assert_eq!(response.json::<ApiError>().id, PlayersError::FORBIDDEN.id);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn admin_player_rejects_banned_admins() -> anyhow::Result<()> {
+        let server = admin_server(
+            PlayerRole::Admin,
+            Player {
+                banned_until: Some(Utc::now() + chrono::TimeDelta::hours(1)),
+                ..Player::test_admin()
+            },
+        )?;
+
+        let response = server.get("/admin").await;
+
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.json::<ApiError>().id,
+            PlayersError::PLAYER_BANNED.id
+        );
+
+        Ok(())
+    }
+
+    fn player_server(player: Player) -> anyhow::Result<TestServer> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .returning(move |_| Box::pin(std::future::ready(Ok(player.clone()))));
+        players_service
+            .expect_touch_last_seen()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let router = Router::new()
+            .route(
+                "/player",
+                get(|player: Player| async move { player.screen_name.0 }),
+            )
+            .with_state(state);
+
+        let mut server = TestServer::new(router)?;
+        server.add_header(AUTHORIZATION, "Bearer valid");
+
+        Ok(server)
+    }
+
+    #[tokio::test]
+    async fn authenticated_is_extracted_once_per_request() -> anyhow::Result<()> {
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_verify_token()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(AuthTokenClaims::test()) }));
+
+        let mut players_service = MockPlayersService::new();
+        players_service
+            .expect_player_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_service
+            .expect_touch_last_seen()
+            .with(mockall::predicate::eq(Player::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let state = Arc::new(
+            MockAppState::default()
+                .with_jwt_service(jwt_service)
+                .with_players_service(players_service),
+        );
+        let router = Router::new()
+            .route(
+                "/both",
+                get(|claims: AuthTokenClaims, player: Player| async move {
+                    assert_eq!(claims.sub, player.id);
+                    player.screen_name.0
+                }),
+            )
+            .with_state(state);
+        let server = TestServer::new(router)?;
+
+        let response = server
+            .get("/both")
+            .add_header(AUTHORIZATION, "Bearer valid")
+            .await;
+
+        response.assert_status_ok();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn player_rejects_banned_players() -> anyhow::Result<()> {
+        let until = Utc::now() + chrono::TimeDelta::hours(1);
+        let server = player_server(Player {
+            banned_until: Some(until),
+            ..Player::test()
+        })?;
+
+        let response = server.get("/player").await;
+
+        response.assert_status(StatusCode::FORBIDDEN);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.id, PlayersError::PLAYER_BANNED.id);
+        assert!(error.dev_message.contains(&until.to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn player_accepts_players_with_expired_ban() -> anyhow::Result<()> {
+        let server = player_server(Player {
+            banned_until: Some(Utc::now() - chrono::TimeDelta::seconds(1)),
+            ..Player::test()
+        })?;
+
+        let response = server.get("/player").await;
+
+        response.assert_status_ok();
+        response.assert_text(Player::test().screen_name.0);
+
+        Ok(())
+    }
 
     #[test]
     fn player_json_snapshot() {
         insta::assert_json_snapshot!(&Player::test());
     }
 
+    #[test]
+    fn public_player_json_snapshot() {
+        insta::assert_json_snapshot!(&PublicPlayer::from(Player::test()));
+    }
+
     #[test]
     fn player_screen_name_from_str_succeeds_if_screen_name_is_valid_of_min_size() {
         assert_eq!(
@@ -181,17 +676,22 @@ mod tests {
             Ok(PlayerScreenName(value))
         );
 
-        let value = "г".repeat(PlayerScreenName::MAX_SIZE / 2);
-        assert_eq!(
-            PlayerScreenName::from_str(&value),
-            Ok(PlayerScreenName(value.clone()))
-        );
-
-        let value_ws = format!("  {value}   ");
-        assert_eq!(
-            PlayerScreenName::from_str(&value_ws),
-            Ok(PlayerScreenName(value))
-        );
+        for value in [
+            "г".repeat(PlayerScreenName::MAX_SIZE),
+            "名".repeat(PlayerScreenName::MAX_SIZE),
+            "😀".repeat(PlayerScreenName::MAX_SIZE),
+        ] {
+            assert_eq!(
+                PlayerScreenName::from_str(&value),
+                Ok(PlayerScreenName(value.clone()))
+            );
+
+            let value_ws = format!("  {value}   ");
+            assert_eq!(
+                PlayerScreenName::from_str(&value_ws),
+                Ok(PlayerScreenName(value))
+            );
+        }
     }
 
     #[test]
@@ -244,16 +744,57 @@ mod tests {
             Err(InvalidPlayerScreenName::ExceedsMaxSize)
         );
 
-        let value = "г".repeat(PlayerScreenName::MAX_SIZE / 2 + 1);
-        assert_eq!(
-            PlayerScreenName::from_str(&value),
-            Err(InvalidPlayerScreenName::ExceedsMaxSize)
-        );
+        for value in [
+            "г".repeat(PlayerScreenName::MAX_SIZE + 1),
+            "名".repeat(PlayerScreenName::MAX_SIZE + 1),
+            "😀".repeat(PlayerScreenName::MAX_SIZE + 1),
+        ] {
+            assert_eq!(
+                PlayerScreenName::from_str(&value),
+                Err(InvalidPlayerScreenName::ExceedsMaxSize)
+            );
+
+            let value_ws = format!("  {value}   ");
+            assert_eq!(
+                PlayerScreenName::from_str(&value_ws),
+                Err(InvalidPlayerScreenName::ExceedsMaxSize)
+            );
+        }
+    }
 
-        let value_ws = format!("  {value}   ");
-        assert_eq!(
-            PlayerScreenName::from_str(&value_ws),
-            Err(InvalidPlayerScreenName::ExceedsMaxSize)
-        );
+    #[test]
+    fn player_screen_name_from_str_succeeds_if_screen_name_has_words_and_punctuation() {
+        for value in [
+            "two words",
+            "under_score",
+            "dash-ed",
+            "Дмитро O'Neil",
+            "名前 1.0!",
+        ] {
+            assert_eq!(
+                PlayerScreenName::from_str(value),
+                Ok(PlayerScreenName(value.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn player_screen_name_from_str_fails_if_screen_name_has_invalid_characters() {
+        for value in [
+            "line\nbreak",
+            "tab\tbed",
+            "null\0",
+            "zero\u{200B}width",
+            "zero\u{200D}joiner",
+            "\u{202E}override",
+            "soft\u{00AD}hyphen",
+            "no\u{00A0}break",
+        ] {
+            assert_eq!(
+                PlayerScreenName::from_str(value),
+                Err(InvalidPlayerScreenName::InvalidCharacters),
+                "{value:?}"
+            );
+        }
     }
 }
@@ -1,31 +1,281 @@
 use crate::players::error::PlayersError;
+use crate::players::friendship::*;
+use crate::players::jwt_service::AuthTokenId;
+use crate::players::notification::*;
+use crate::players::page::Page;
+use crate::players::password::PasswordHash;
 use crate::players::player::*;
+use crate::players::preferences::PlayerPreferences;
+use crate::players::refresh_token::*;
+use crate::players::report::*;
 use crate::players::sign_in_method::*;
+use crate::slow_query_log::log_if_slow;
 use anyhow::Context;
-use sqlx::{PgPool, query, query_as};
+use chrono::{DateTime, TimeDelta, Utc};
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool, query, query_as, query_scalar};
 use std::ops::DerefMut;
+use std::time::Duration;
 
 /// Defines db operations with [Player]s.
 #[cfg_attr(test, mockall::automock)]
 pub trait PlayersDb {
-    /// Creates a new [Player] with the given [SignInMethod] in the database.
+    /// Creates a new [Player] with the given [SignInMethod] in the database and returns it.
+    /// If the [PlayerScreenName] is taken, retries with [PlayerScreenName::random] up to
+    /// [SCREEN_NAME_ATTEMPTS] times in total before failing with [PlayersError::ScreenNameTaken].
     fn create_player_with_sign_in_method(
         &self,
         player: &Player,
         sign_in_method: &SignInMethod,
+    ) -> impl Future<Output = Result<Player, PlayersError>> + Send;
+
+    /// Adds the given [SignInMethod] to an existing [Player] in the database, removing their
+    /// [SignInMethod::Anonymous] as they are no longer a guest, in a single transaction.
+    /// Fails with [PlayersError::SignInMethodAlreadyLinked] if it belongs to a [Player] already.
+    fn add_sign_in_method(
+        &self,
+        player_id: &PlayerId,
+        sign_in_method: &SignInMethod,
     ) -> impl Future<Output = Result<(), PlayersError>> + Send;
 
     /// Finds a [Player] with the matching [SignInMethod] in the database.
+    /// [SignInMethod::EmailPassword] is matched by its [Email] only, [SignInMethod::Anonymous]
+    /// never matches as [DeviceId]s are not secret.
     fn find_player_with_sign_in_method(
         &self,
         sign_in_method: &SignInMethod,
     ) -> impl Future<Output = Result<Player, PlayersError>> + Send;
 
+    /// Finds all [LinkedSignInMethod]s of the [Player] with the given [PlayerId] in the database.
+    fn find_sign_in_methods(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<Vec<LinkedSignInMethod>, PlayersError>> + Send;
+
     /// Finds a [Player] by the given [PlayerId] in the database.
     fn find_player_by_id(
         &self,
         player_id: &PlayerId,
     ) -> impl Future<Output = Result<Player, PlayersError>> + Send;
+
+    /// Finds the [Player]s with the given [PlayerId]s in the database with a single query,
+    /// ordered by [PlayerId]. Unknown [PlayerId]s are skipped.
+    fn find_players_by_ids(
+        &self,
+        player_ids: &[PlayerId],
+    ) -> impl Future<Output = Result<Vec<Player>, PlayersError>> + Send;
+
+    /// Lists at most `limit` [Player]s ordered by [PlayerId], which is time ordered, starting
+    /// after the given [PlayerId] if any.
+    fn list_players(
+        &self,
+        after: Option<PlayerId>,
+        limit: u16,
+    ) -> impl Future<Output = Result<Page<Player>, PlayersError>> + Send;
+
+    /// Sets when the [Player] with the given [PlayerId] was last seen to now, unless it has been
+    /// set within [LAST_SEEN_THROTTLE] already.
+    fn touch_last_seen(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Counts the [Player]s last seen after the given time in the database.
+    fn count_players_seen_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> impl Future<Output = Result<i64, PlayersError>> + Send;
+
+    /// Sets until when the [Player] with the given [PlayerId] is banned, [None] lifts the ban.
+    /// Fails with [PlayersError::PlayerNotFound] if there is no such [Player].
+    fn set_banned_until(
+        &self,
+        player_id: &PlayerId,
+        until: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Finds the [PlayerPreferences] of the [Player] with the given [PlayerId] in the database,
+    /// [PlayerPreferences::default] if they have never been set.
+    fn get_preferences(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<PlayerPreferences, PlayersError>> + Send;
+
+    /// Replaces the [PlayerPreferences] of the [Player] with the given [PlayerId] in the database.
+    /// Fails with [PlayersError::PlayerNotFound] if there is no such [Player].
+    fn set_preferences(
+        &self,
+        player_id: &PlayerId,
+        preferences: &PlayerPreferences,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Deletes the [Player] with the given [PlayerId] with all their [SignInMethod]s, refresh
+    /// tokens and notifications from the database in a single transaction. [Report]s by and of
+    /// the [Player] are kept for moderation, with the [Report::reporter_id] cleared.
+    /// Fails with [PlayersError::PlayerNotFound] if there is no such [Player].
+    fn delete_player(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Whether a [Player] with the given [PlayerScreenName], compared case-insensitively, exists
+    /// in the database.
+    fn screen_name_exists(
+        &self,
+        screen_name: &PlayerScreenName,
+    ) -> impl Future<Output = Result<bool, PlayersError>> + Send;
+
+    /// Finds a [Player] registered with the given [Email] and their [PasswordHash] in the
+    /// database.
+    fn find_player_with_email(
+        &self,
+        email: &Email,
+    ) -> impl Future<Output = Result<(Player, PasswordHash), PlayersError>> + Send;
+
+    /// Stores the given [StoredRefreshToken] in the database.
+    fn create_refresh_token(
+        &self,
+        refresh_token: &StoredRefreshToken,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Finds a [StoredRefreshToken] by the given [RefreshTokenHash] in the database.
+    /// Fails with [PlayersError::RefreshTokenInvalid] if there is none.
+    fn find_refresh_token(
+        &self,
+        hash: &RefreshTokenHash,
+    ) -> impl Future<Output = Result<StoredRefreshToken, PlayersError>> + Send;
+
+    /// Marks the [StoredRefreshToken] with the given [RefreshTokenHash] as rotated in the
+    /// database. Returns `false` if it has already been rotated.
+    fn rotate_refresh_token(
+        &self,
+        hash: &RefreshTokenHash,
+    ) -> impl Future<Output = Result<bool, PlayersError>> + Send;
+
+    /// Revokes all [StoredRefreshToken]s of the given [RefreshTokenFamilyId] in the database.
+    fn revoke_refresh_token_family(
+        &self,
+        family_id: &RefreshTokenFamilyId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Stores the given [AuthTokenId] as revoked until the token expires at `expires_at`, and
+    /// forgets the revoked tokens that have expired already.
+    fn revoke_token(
+        &self,
+        jti: &AuthTokenId,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Whether the token with the given [AuthTokenId] has been revoked.
+    fn is_token_revoked(
+        &self,
+        jti: &AuthTokenId,
+    ) -> impl Future<Output = Result<bool, PlayersError>> + Send;
+    /// Stores the given [Notification] in the inbox of the [Player] with the given [PlayerId].
+    fn create_notification(
+        &self,
+        player_id: &PlayerId,
+        notification: &Notification,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Finds at most `limit` [Notification]s of the [Player] with the given [PlayerId], newest
+    /// first, only unread ones if `unread_only` is set and only older than `before` if given.
+    fn find_notifications(
+        &self,
+        player_id: &PlayerId,
+        unread_only: bool,
+        before: Option<NotificationId>,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<Notification>, PlayersError>> + Send;
+
+    /// Marks the [Notification]s with the given [NotificationId]s of the [Player] with the given
+    /// [PlayerId] as read. [NotificationId]s of other [Player]s are ignored.
+    fn mark_notifications_read(
+        &self,
+        player_id: &PlayerId,
+        ids: &[NotificationId],
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+    /// Finds the [Friendship] between the [Player]s with the given [PlayerId]s in the database,
+    /// regardless of which of them has requested it. If they have blocked each other, the
+    /// oldest [FriendshipState::Blocked] one is returned.
+    fn find_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> impl Future<Output = Result<Option<Friendship>, PlayersError>> + Send;
+
+    /// Stores the given [Friendship] in the database unless there is one between the same
+    /// [Player]s already.
+    fn create_friendship(
+        &self,
+        friendship: &Friendship,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Sets the [FriendshipState] of the [Friendship] requested by the [Player] with the given
+    /// requester [PlayerId] from the one with the given addressee [PlayerId].
+    fn set_friendship_state(
+        &self,
+        requester_id: &PlayerId,
+        addressee_id: &PlayerId,
+        state: FriendshipState,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Deletes the [Friendship] between the [Player]s with the given [PlayerId]s from the
+    /// database unless it is [FriendshipState::Blocked].
+    fn delete_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Replaces the [Friendship] between the [Player] with the given blocker [PlayerId] and the
+    /// one with the given blocked [PlayerId] with a [FriendshipState::Blocked] one in a single
+    /// transaction. A block by the other [Player] is kept alongside it.
+    fn block_player(
+        &self,
+        blocker_id: &PlayerId,
+        blocked_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Deletes the block of the [Player] with the given blocked [PlayerId] by the one with the
+    /// given blocker [PlayerId] from the database.
+    fn unblock_player(
+        &self,
+        blocker_id: &PlayerId,
+        blocked_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Finds the [Friend]s of the [Player] with the given [PlayerId] in the database, oldest
+    /// [Friendship] first. [FriendshipState::Blocked] [Friendship]s are skipped.
+    fn find_friends(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<Vec<Friend>, PlayersError>> + Send;
+
+    /// Creates the given [Report] in the database, unless its reporter has already made
+    /// [Report::MAX_PER_WINDOW] [Report]s within [Report::RATE_LIMIT_WINDOW] before it, in which
+    /// case fails with [PlayersError::ReportRateLimited]. Concurrent [Report]s of the same
+    /// reporter are counted one after another.
+    fn create_report(
+        &self,
+        report: &Report,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Lists at most `limit` [ReportStatus::Open] [Report]s after the given [ReportId] in the
+    /// database, oldest first.
+    fn list_open_reports(
+        &self,
+        after: Option<ReportId>,
+        limit: u16,
+    ) -> impl Future<Output = Result<Page<Report>, PlayersError>> + Send;
+
+    /// Sets the [ReportStatus] of the [Report] with the given [ReportId] to
+    /// [ReportStatus::Resolved] in the database. Fails with [PlayersError::ReportNotFound] if
+    /// there is no such [Report].
+    fn resolve_report(
+        &self,
+        report_id: &ReportId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
 }
 
 impl PlayersDb for PgPool {
@@ -33,36 +283,48 @@ impl PlayersDb for PgPool {
         &self,
         player: &Player,
         sign_in_method: &SignInMethod,
+    ) -> Result<Player, PlayersError> {
+        let mut player = player.clone();
+        let mut attempts = 1;
+
+        loop {
+            match insert_player_with_sign_in_method(self, &player, sign_in_method).await {
+                Err(PlayersError::ScreenNameTaken) if attempts < SCREEN_NAME_ATTEMPTS => {
+                    attempts += 1;
+                    player.screen_name = PlayerScreenName::random();
+                }
+                result => return result.map(|()| player),
+            }
+        }
+    }
+
+    async fn add_sign_in_method(
+        &self,
+        player_id: &PlayerId,
+        sign_in_method: &SignInMethod,
     ) -> Result<(), PlayersError> {
         let mut tx = self.begin().await.context("begin transaction")?;
 
+        insert_sign_in_method(tx.deref_mut(), player_id, sign_in_method)
+            .await
+            .map_err(|e| {
+                if is_unique_violation(&e) {
+                    PlayersError::SignInMethodAlreadyLinked
+                } else {
+                    PlayersError::Internal(anyhow::Error::new(e).context("add sign in method"))
+                }
+            })?;
+
         query!(
             r#"
-            insert into player (id, screen_name, joined_at)
-            values ($1, $2, $3)
+            delete from anonymous_sign_in_method
+            where player_id = $1
             "#,
-            &player.id as &PlayerId,
-            &player.screen_name as &PlayerScreenName,
-            &player.joined_at as &PlayerJoinedAt
+            player_id as &PlayerId
         )
         .execute(tx.deref_mut())
         .await
-        .context("create player")?;
-
-        match sign_in_method {
-            SignInMethod::ThirdParty(third_party) => query!(
-                r#"
-                insert into third_party_sign_in_method (provider, user_id, player_id)
-                values ($1, $2, $3)
-                "#,
-                &third_party.provider as &ThirdPartySignInProvider,
-                &third_party.user_id as &ThirdPartySignInUserId,
-                &player.id as &PlayerId
-            )
-            .execute(tx.deref_mut()),
-        }
-        .await
-        .context("create sign in method")?;
+        .context("delete anonymous sign in methods")?;
 
         tx.commit().await.context("commit transaction")?;
 
@@ -74,29 +336,116 @@ impl PlayersDb for PgPool {
         sign_in_method: &SignInMethod,
     ) -> Result<Player, PlayersError> {
         let player = match sign_in_method {
-            SignInMethod::ThirdParty(third_party) => query_as!(
-                Player,
-                r#"
-                select
-                    p.id as "id: PlayerId",
-                    p.screen_name as "screen_name: PlayerScreenName",
-                    p.joined_at as "joined_at: PlayerJoinedAt"
-                from player p
-                join third_party_sign_in_method t on p.id = t.player_id
-                where t.provider = $1 and t.user_id = $2
-                "#,
-                &third_party.provider as &ThirdPartySignInProvider,
-                &third_party.user_id as &ThirdPartySignInUserId
-            )
-            .fetch_optional(self),
+            SignInMethod::ThirdParty(third_party) => {
+                query_as!(
+                    Player,
+                    r#"
+                    select
+                        p.id as "id: PlayerId",
+                        p.screen_name as "screen_name: PlayerScreenName",
+                        p.joined_at as "joined_at: PlayerJoinedAt",
+                        p.role as "role: PlayerRole",
+                        p.last_seen_at,
+                        p.banned_until
+                    from player p
+                    join third_party_sign_in_method t on p.id = t.player_id
+                    where t.provider = $1 and t.user_id = $2
+                    "#,
+                    &third_party.provider as &ThirdPartySignInProvider,
+                    &third_party.user_id as &ThirdPartySignInUserId
+                )
+                .fetch_optional(self)
+                .await
+            }
+            SignInMethod::EmailPassword(email_password) => {
+                query_as!(
+                    Player,
+                    r#"
+                    select
+                        p.id as "id: PlayerId",
+                        p.screen_name as "screen_name: PlayerScreenName",
+                        p.joined_at as "joined_at: PlayerJoinedAt",
+                        p.role as "role: PlayerRole",
+                        p.last_seen_at,
+                        p.banned_until
+                    from player p
+                    join email_password_sign_in_method e on p.id = e.player_id
+                    where e.email = $1
+                    "#,
+                    &email_password.email as &Email
+                )
+                .fetch_optional(self)
+                .await
+            }
+            // Device ids are not secret, so guests can't be found by them.
+            SignInMethod::Anonymous { .. } => Ok(None),
         }
-        .await
         .context("find player with sign in method")?
         .ok_or(PlayersError::PlayerNotFound)?;
 
         Ok(player)
     }
 
+    async fn find_sign_in_methods(
+        &self,
+        player_id: &PlayerId,
+    ) -> Result<Vec<LinkedSignInMethod>, PlayersError> {
+        let providers = query_scalar!(
+            r#"
+            select provider as "provider: ThirdPartySignInProvider"
+            from third_party_sign_in_method
+            where player_id = $1
+            order by provider
+            "#,
+            player_id as &PlayerId
+        )
+        .fetch_all(self)
+        .await
+        .context("find third party sign in methods")?;
+
+        let emails = query_scalar!(
+            r#"
+            select email as "email: Email"
+            from email_password_sign_in_method
+            where player_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .fetch_all(self)
+        .await
+        .context("find email password sign in methods")?;
+
+        let device_ids = query_scalar!(
+            r#"
+            select device_id as "device_id: DeviceId"
+            from anonymous_sign_in_method
+            where player_id = $1
+            order by device_id
+            "#,
+            player_id as &PlayerId
+        )
+        .fetch_all(self)
+        .await
+        .context("find anonymous sign in methods")?;
+
+        let sign_in_methods = providers
+            .into_iter()
+            .map(|provider| LinkedSignInMethod::ThirdParty { provider })
+            .chain(
+                emails
+                    .into_iter()
+                    .map(|email| LinkedSignInMethod::EmailPassword { email }),
+            )
+            .chain(
+                device_ids
+                    .into_iter()
+                    .map(|device_id| LinkedSignInMethod::Anonymous { device_id }),
+            )
+            .collect();
+
+        Ok(sign_in_methods)
+    }
+
     async fn find_player_by_id(&self, player_id: &PlayerId) -> Result<Player, PlayersError> {
         query_as!(
             Player,
@@ -104,7 +453,10 @@ impl PlayersDb for PgPool {
             select
                 id as "id: PlayerId",
                 screen_name as "screen_name: PlayerScreenName",
-                joined_at as "joined_at: PlayerJoinedAt"
+                joined_at as "joined_at: PlayerJoinedAt",
+                role as "role: PlayerRole",
+                last_seen_at,
+                banned_until
             from player
             where id = $1
             "#,
@@ -115,4 +467,2041 @@ impl PlayersDb for PgPool {
         .context("find player by id")?
         .ok_or(PlayersError::PlayerNotFound)
     }
+
+    async fn find_players_by_ids(
+        &self,
+        player_ids: &[PlayerId],
+    ) -> Result<Vec<Player>, PlayersError> {
+        let player_ids = player_ids.iter().map(|id| id.0).collect::<Vec<_>>();
+
+        let players = query_as!(
+            Player,
+            r#"
+            select
+                id as "id: PlayerId",
+                screen_name as "screen_name: PlayerScreenName",
+                joined_at as "joined_at: PlayerJoinedAt",
+                role as "role: PlayerRole",
+                last_seen_at,
+                banned_until
+            from player
+            where id = any($1)
+            order by id
+            "#,
+            &player_ids
+        )
+        .fetch_all(self)
+        .await
+        .context("find players by ids")?;
+
+        Ok(players)
+    }
+
+    async fn list_players(
+        &self,
+        after: Option<PlayerId>,
+        limit: u16,
+    ) -> Result<Page<Player>, PlayersError> {
+        let players = query_as!(
+            Player,
+            r#"
+            select
+                id as "id: PlayerId",
+                screen_name as "screen_name: PlayerScreenName",
+                joined_at as "joined_at: PlayerJoinedAt",
+                role as "role: PlayerRole",
+                last_seen_at,
+                banned_until
+            from player
+            where ($1::uuid is null or id > $1)
+            order by id
+            limit $2
+            "#,
+            after.map(|after| after.0),
+            i64::from(limit) + 1
+        )
+        .fetch_all(self)
+        .await
+        .context("list players")?;
+
+        Ok(Page::new(players, usize::from(limit)))
+    }
+
+    async fn touch_last_seen(&self, player_id: &PlayerId) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            update player
+            set last_seen_at = now()
+            where id = $1
+              and (last_seen_at is null or last_seen_at <= now() - make_interval(secs => $2))
+            "#,
+            player_id as &PlayerId,
+            LAST_SEEN_THROTTLE.num_seconds() as f64
+        )
+        .execute(self)
+        .await
+        .context("touch last seen")?;
+
+        Ok(())
+    }
+
+    async fn count_players_seen_since(&self, since: DateTime<Utc>) -> Result<i64, PlayersError> {
+        let count = query_scalar!(
+            r#"
+            select count(*) as "count!"
+            from player
+            where last_seen_at > $1
+            "#,
+            since
+        )
+        .fetch_one(self)
+        .await
+        .context("count players seen since")?;
+
+        Ok(count)
+    }
+
+    async fn set_banned_until(
+        &self,
+        player_id: &PlayerId,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<(), PlayersError> {
+        let result = query!(
+            r#"
+            update player
+            set banned_until = $2
+            where id = $1
+            "#,
+            player_id as &PlayerId,
+            until
+        )
+        .execute(self)
+        .await
+        .context("set banned until")?;
+
+        if result.rows_affected() == 0 {
+            Err(PlayersError::PlayerNotFound)?
+        }
+
+        Ok(())
+    }
+
+    async fn get_preferences(
+        &self,
+        player_id: &PlayerId,
+    ) -> Result<PlayerPreferences, PlayersError> {
+        let preferences = query_scalar!(
+            r#"
+            select preferences as "preferences: Json<PlayerPreferences>"
+            from player
+            where id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .fetch_optional(self)
+        .await
+        .context("get preferences")?
+        .ok_or(PlayersError::PlayerNotFound)?;
+
+        Ok(preferences
+            .map(|Json(preferences)| preferences)
+            .unwrap_or_default())
+    }
+
+    async fn set_preferences(
+        &self,
+        player_id: &PlayerId,
+        preferences: &PlayerPreferences,
+    ) -> Result<(), PlayersError> {
+        let result = query!(
+            r#"
+            update player
+            set preferences = $2
+            where id = $1
+            "#,
+            player_id as &PlayerId,
+            Json(preferences) as _
+        )
+        .execute(self)
+        .await
+        .context("set preferences")?;
+
+        if result.rows_affected() == 0 {
+            Err(PlayersError::PlayerNotFound)?
+        }
+
+        Ok(())
+    }
+
+    async fn delete_player(&self, player_id: &PlayerId) -> Result<(), PlayersError> {
+        let mut tx = self.begin().await.context("begin transaction")?;
+
+        query!(
+            r#"
+            delete from third_party_sign_in_method
+            where player_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete third party sign in methods")?;
+
+        query!(
+            r#"
+            delete from email_password_sign_in_method
+            where player_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete email password sign in methods")?;
+
+        query!(
+            r#"
+            delete from anonymous_sign_in_method
+            where player_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete anonymous sign in methods")?;
+
+        query!(
+            r#"
+            delete from refresh_token
+            where player_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete refresh tokens")?;
+
+        query!(
+            r#"
+            delete from notification
+            where player_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete notifications")?;
+
+        query!(
+            r#"
+            delete from friendship
+            where requester_id = $1 or addressee_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete friendships")?;
+
+        query!(
+            r#"
+            update report
+            set reporter_id = null
+            where reporter_id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("anonymise reports")?;
+
+        let result = query!(
+            r#"
+            delete from player
+            where id = $1
+            "#,
+            player_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete player")?;
+
+        if result.rows_affected() == 0 {
+            Err(PlayersError::PlayerNotFound)?
+        }
+
+        tx.commit().await.context("commit transaction")?;
+
+        Ok(())
+    }
+
+    async fn screen_name_exists(
+        &self,
+        screen_name: &PlayerScreenName,
+    ) -> Result<bool, PlayersError> {
+        let exists = query_scalar!(
+            r#"
+            select exists(select 1 from player where lower(screen_name) = lower($1)) as "exists!"
+            "#,
+            screen_name as &PlayerScreenName
+        )
+        .fetch_one(self)
+        .await
+        .context("check screen name exists")?;
+
+        Ok(exists)
+    }
+
+    async fn find_player_with_email(
+        &self,
+        email: &Email,
+    ) -> Result<(Player, PasswordHash), PlayersError> {
+        let row = query!(
+            r#"
+            select
+                p.id as "id: PlayerId",
+                p.screen_name as "screen_name: PlayerScreenName",
+                p.joined_at as "joined_at: PlayerJoinedAt",
+                p.role as "role: PlayerRole",
+                p.last_seen_at,
+                p.banned_until,
+                e.password_hash as "password_hash: PasswordHash"
+            from player p
+            join email_password_sign_in_method e on p.id = e.player_id
+            where e.email = $1
+            "#,
+            email as &Email
+        )
+        .fetch_optional(self)
+        .await
+        .context("find player with email")?
+        .ok_or(PlayersError::PlayerNotFound)?;
+
+        let player = Player {
+            id: row.id,
+            screen_name: row.screen_name,
+            joined_at: row.joined_at,
+            role: row.role,
+            last_seen_at: row.last_seen_at,
+            banned_until: row.banned_until,
+        };
+
+        Ok((player, row.password_hash))
+    }
+
+    async fn create_refresh_token(
+        &self,
+        refresh_token: &StoredRefreshToken,
+    ) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            insert into refresh_token (token_hash, family_id, player_id, expires_at, rotated, revoked)
+            values ($1, $2, $3, $4, $5, $6)
+            "#,
+            &refresh_token.hash as &RefreshTokenHash,
+            &refresh_token.family_id as &RefreshTokenFamilyId,
+            &refresh_token.player_id as &PlayerId,
+            refresh_token.expires_at,
+            refresh_token.rotated,
+            refresh_token.revoked
+        )
+        .execute(self)
+        .await
+        .context("create refresh token")?;
+
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        hash: &RefreshTokenHash,
+    ) -> Result<StoredRefreshToken, PlayersError> {
+        query_as!(
+            StoredRefreshToken,
+            r#"
+            select
+                token_hash as "hash: RefreshTokenHash",
+                family_id as "family_id: RefreshTokenFamilyId",
+                player_id as "player_id: PlayerId",
+                expires_at,
+                rotated,
+                revoked
+            from refresh_token
+            where token_hash = $1
+            "#,
+            hash as &RefreshTokenHash
+        )
+        .fetch_optional(self)
+        .await
+        .context("find refresh token")?
+        .ok_or(PlayersError::RefreshTokenInvalid)
+    }
+
+    async fn rotate_refresh_token(&self, hash: &RefreshTokenHash) -> Result<bool, PlayersError> {
+        let result = query!(
+            r#"
+            update refresh_token
+            set rotated = true
+            where token_hash = $1 and not rotated
+            "#,
+            hash as &RefreshTokenHash
+        )
+        .execute(self)
+        .await
+        .context("rotate refresh token")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke_refresh_token_family(
+        &self,
+        family_id: &RefreshTokenFamilyId,
+    ) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            update refresh_token
+            set revoked = true
+            where family_id = $1
+            "#,
+            family_id as &RefreshTokenFamilyId
+        )
+        .execute(self)
+        .await
+        .context("revoke refresh token family")?;
+
+        Ok(())
+    }
+
+    async fn revoke_token(
+        &self,
+        jti: &AuthTokenId,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), PlayersError> {
+        let mut tx = self.begin().await.context("begin transaction")?;
+
+        query!(
+            r#"
+            delete from revoked_token
+            where expires_at < now()
+            "#
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete expired revoked tokens")?;
+
+        query!(
+            r#"
+            insert into revoked_token (jti, expires_at)
+            values ($1, $2)
+            on conflict (jti) do nothing
+            "#,
+            jti as &AuthTokenId,
+            expires_at
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("revoke token")?;
+
+        tx.commit().await.context("commit transaction")?;
+
+        Ok(())
+    }
+
+    async fn is_token_revoked(&self, jti: &AuthTokenId) -> Result<bool, PlayersError> {
+        let is_revoked = query_scalar!(
+            r#"
+            select exists(select 1 from revoked_token where jti = $1) as "is_revoked!"
+            "#,
+            jti as &AuthTokenId
+        )
+        .fetch_one(self)
+        .await
+        .context("check revoked token")?;
+
+        Ok(is_revoked)
+    }
+    async fn create_notification(
+        &self,
+        player_id: &PlayerId,
+        notification: &Notification,
+    ) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            insert into notification (id, player_id, kind, message, created_at, read)
+            values ($1, $2, $3, $4, $5, $6)
+            "#,
+            &notification.id as &NotificationId,
+            player_id as &PlayerId,
+            notification.kind as NotificationKind,
+            notification.message,
+            notification.created_at,
+            notification.read
+        )
+        .execute(self)
+        .await
+        .context("create notification")?;
+
+        Ok(())
+    }
+
+    async fn find_notifications(
+        &self,
+        player_id: &PlayerId,
+        unread_only: bool,
+        before: Option<NotificationId>,
+        limit: i64,
+    ) -> Result<Vec<Notification>, PlayersError> {
+        let notifications = query_as!(
+            Notification,
+            r#"
+            select
+                id as "id: NotificationId",
+                kind as "kind: NotificationKind",
+                message,
+                created_at,
+                read
+            from notification
+            where player_id = $1
+              and (not $2 or not read)
+              and ($3::uuid is null or id < $3)
+            order by id desc
+            limit $4
+            "#,
+            player_id as &PlayerId,
+            unread_only,
+            before.map(|before| before.0),
+            limit
+        )
+        .fetch_all(self)
+        .await
+        .context("find notifications")?;
+
+        Ok(notifications)
+    }
+
+    async fn mark_notifications_read(
+        &self,
+        player_id: &PlayerId,
+        ids: &[NotificationId],
+    ) -> Result<(), PlayersError> {
+        let ids = ids.iter().map(|id| id.0).collect::<Vec<_>>();
+
+        query!(
+            r#"
+            update notification
+            set read = true
+            where player_id = $1 and id = any($2)
+            "#,
+            player_id as &PlayerId,
+            &ids
+        )
+        .execute(self)
+        .await
+        .context("mark notifications read")?;
+
+        Ok(())
+    }
+    async fn find_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<Option<Friendship>, PlayersError> {
+        let friendship = query_as!(
+            Friendship,
+            r#"
+            select
+                requester_id as "requester_id: PlayerId",
+                addressee_id as "addressee_id: PlayerId",
+                state as "state: FriendshipState"
+            from friendship
+            where (requester_id = $1 and addressee_id = $2)
+               or (requester_id = $2 and addressee_id = $1)
+            order by state = 'Blocked' desc, created_at
+            limit 1
+            "#,
+            player_id as &PlayerId,
+            other_id as &PlayerId
+        )
+        .fetch_optional(self)
+        .await
+        .context("find friendship")?;
+
+        Ok(friendship)
+    }
+
+    async fn create_friendship(&self, friendship: &Friendship) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            insert into friendship (requester_id, addressee_id, state)
+            values ($1, $2, $3)
+            on conflict do nothing
+            "#,
+            friendship.requester_id as PlayerId,
+            friendship.addressee_id as PlayerId,
+            friendship.state as FriendshipState
+        )
+        .execute(self)
+        .await
+        .context("create friendship")?;
+
+        Ok(())
+    }
+
+    async fn set_friendship_state(
+        &self,
+        requester_id: &PlayerId,
+        addressee_id: &PlayerId,
+        state: FriendshipState,
+    ) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            update friendship
+            set state = $3
+            where requester_id = $1 and addressee_id = $2
+            "#,
+            requester_id as &PlayerId,
+            addressee_id as &PlayerId,
+            state as FriendshipState
+        )
+        .execute(self)
+        .await
+        .context("set friendship state")?;
+
+        Ok(())
+    }
+
+    async fn delete_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            delete from friendship
+            where ((requester_id = $1 and addressee_id = $2)
+                or (requester_id = $2 and addressee_id = $1))
+              and state <> 'Blocked'
+            "#,
+            player_id as &PlayerId,
+            other_id as &PlayerId
+        )
+        .execute(self)
+        .await
+        .context("delete friendship")?;
+
+        Ok(())
+    }
+
+    async fn block_player(
+        &self,
+        blocker_id: &PlayerId,
+        blocked_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        let mut tx = self.begin().await.context("begin transaction")?;
+
+        query!(
+            r#"
+            delete from friendship
+            where ((requester_id = $1 and addressee_id = $2)
+                or (requester_id = $2 and addressee_id = $1))
+              and not (requester_id = $2 and state = 'Blocked')
+            "#,
+            blocker_id as &PlayerId,
+            blocked_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("delete friendship")?;
+
+        query!(
+            r#"
+            insert into friendship (requester_id, addressee_id, state)
+            values ($1, $2, 'Blocked')
+            on conflict do nothing
+            "#,
+            blocker_id as &PlayerId,
+            blocked_id as &PlayerId
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("block player")?;
+
+        tx.commit().await.context("commit transaction")?;
+
+        Ok(())
+    }
+
+    async fn unblock_player(
+        &self,
+        blocker_id: &PlayerId,
+        blocked_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        query!(
+            r#"
+            delete from friendship
+            where requester_id = $1 and addressee_id = $2 and state = 'Blocked'
+            "#,
+            blocker_id as &PlayerId,
+            blocked_id as &PlayerId
+        )
+        .execute(self)
+        .await
+        .context("unblock player")?;
+
+        Ok(())
+    }
+
+    async fn find_friends(&self, player_id: &PlayerId) -> Result<Vec<Friend>, PlayersError> {
+        let rows = query!(
+            r#"
+            select
+                p.id as "id: PlayerId",
+                p.screen_name as "screen_name: PlayerScreenName",
+                p.joined_at as "joined_at: PlayerJoinedAt",
+                p.last_seen_at,
+                f.state as "state: FriendshipState",
+                f.addressee_id = $1 as "incoming!"
+            from friendship f
+            join player p on p.id = case when f.requester_id = $1 then f.addressee_id else f.requester_id end
+            where (f.requester_id = $1 or f.addressee_id = $1)
+              and f.state <> 'Blocked'
+            order by f.created_at
+            "#,
+            player_id as &PlayerId
+        )
+        .fetch_all(self)
+        .await
+        .context("find friends")?;
+
+        let friends = rows
+            .into_iter()
+            .map(|row| Friend {
+                player: PublicPlayer {
+                    id: row.id,
+                    screen_name: row.screen_name,
+                    joined_at: row.joined_at,
+                    last_seen_at: row.last_seen_at,
+                },
+                state: row.state,
+                incoming: row.incoming,
+            })
+            .collect();
+
+        Ok(friends)
+    }
+
+    async fn create_report(&self, report: &Report) -> Result<(), PlayersError> {
+        let mut tx = self.begin().await.context("begin transaction")?;
+
+        // Locks the reporter, so that their concurrent reports can't all pass the count below.
+        query!(
+            r#"
+            select id
+            from player
+            where id = $1
+            for no key update
+            "#,
+            report.reporter_id as Option<PlayerId>
+        )
+        .fetch_optional(tx.deref_mut())
+        .await
+        .context("lock reporter")?;
+
+        let recent_reports = query_scalar!(
+            r#"
+            select count(*) as "count!"
+            from report
+            where reporter_id = $1 and created_at > $2
+            "#,
+            report.reporter_id as Option<PlayerId>,
+            report.created_at - Report::RATE_LIMIT_WINDOW
+        )
+        .fetch_one(tx.deref_mut())
+        .await
+        .context("count recent reports")?;
+        if recent_reports >= Report::MAX_PER_WINDOW {
+            Err(PlayersError::ReportRateLimited)?
+        }
+
+        query!(
+            r#"
+            insert into report (id, reporter_id, reported_id, game_id, reason, created_at, status)
+            values ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            report.id as ReportId,
+            report.reporter_id as Option<PlayerId>,
+            report.reported_id as PlayerId,
+            report.game_id,
+            report.reason,
+            report.created_at,
+            report.status as ReportStatus
+        )
+        .execute(tx.deref_mut())
+        .await
+        .context("create report")?;
+
+        tx.commit().await.context("commit transaction")?;
+
+        Ok(())
+    }
+
+    async fn list_open_reports(
+        &self,
+        after: Option<ReportId>,
+        limit: u16,
+    ) -> Result<Page<Report>, PlayersError> {
+        let reports = query_as!(
+            Report,
+            r#"
+            select
+                id as "id: ReportId",
+                reporter_id as "reporter_id: PlayerId",
+                reported_id as "reported_id: PlayerId",
+                game_id,
+                reason,
+                created_at,
+                status as "status: ReportStatus"
+            from report
+            where status = 'Open'
+              and ($1::uuid is null or id > $1)
+            order by id
+            limit $2
+            "#,
+            after.map(|after| after.0),
+            i64::from(limit) + 1
+        )
+        .fetch_all(self)
+        .await
+        .context("list open reports")?;
+
+        Ok(Page::new(reports, usize::from(limit)))
+    }
+
+    async fn resolve_report(&self, report_id: &ReportId) -> Result<(), PlayersError> {
+        let result = query!(
+            r#"
+            update report
+            set status = 'Resolved'
+            where id = $1
+            "#,
+            report_id as &ReportId
+        )
+        .execute(self)
+        .await
+        .context("resolve report")?;
+
+        if result.rows_affected() == 0 {
+            Err(PlayersError::ReportNotFound)?
+        }
+
+        Ok(())
+    }
+}
+
+/// How often [PlayersDb::touch_last_seen] updates when a [Player] was last seen at most, to avoid
+/// writing on every request.
+pub const LAST_SEEN_THROTTLE: TimeDelta = TimeDelta::minutes(1);
+
+/// How many [PlayerScreenName]s [PlayersDb::create_player_with_sign_in_method] tries before
+/// failing with [PlayersError::ScreenNameTaken].
+pub const SCREEN_NAME_ATTEMPTS: usize = 5;
+
+/// Case-insensitive unique index of `player.screen_name`.
+const SCREEN_NAME_UNIQUE_INDEX: &str = "player_screen_name_unique_idx";
+
+/// Inserts the given [Player] with the given [SignInMethod] in a single transaction.
+async fn insert_player_with_sign_in_method(
+    pg_pool: &PgPool,
+    player: &Player,
+    sign_in_method: &SignInMethod,
+) -> Result<(), PlayersError> {
+    let mut tx = pg_pool.begin().await.context("begin transaction")?;
+
+    query!(
+        r#"
+        insert into player (id, screen_name, joined_at)
+        values ($1, $2, $3)
+        "#,
+        &player.id as &PlayerId,
+        &player.screen_name as &PlayerScreenName,
+        &player.joined_at as &PlayerJoinedAt
+    )
+    .execute(tx.deref_mut())
+    .await
+    .map_err(|e| {
+        if is_unique_violation_of(&e, SCREEN_NAME_UNIQUE_INDEX) {
+            PlayersError::ScreenNameTaken
+        } else {
+            PlayersError::Internal(anyhow::Error::new(e).context("create player"))
+        }
+    })?;
+
+    insert_sign_in_method(tx.deref_mut(), &player.id, sign_in_method)
+        .await
+        .map_err(|e| match sign_in_method {
+            SignInMethod::EmailPassword(_) if is_unique_violation(&e) => {
+                PlayersError::EmailAlreadyRegistered
+            }
+            _ => PlayersError::Internal(anyhow::Error::new(e).context("create sign in method")),
+        })?;
+
+    tx.commit().await.context("commit transaction")?;
+
+    Ok(())
+}
+
+/// Inserts the given [SignInMethod] of the [Player] with the given [PlayerId].
+async fn insert_sign_in_method(
+    conn: &mut PgConnection,
+    player_id: &PlayerId,
+    sign_in_method: &SignInMethod,
+) -> Result<(), sqlx::Error> {
+    match sign_in_method {
+        SignInMethod::ThirdParty(third_party) => {
+            query!(
+                r#"
+                insert into third_party_sign_in_method (provider, user_id, player_id)
+                values ($1, $2, $3)
+                "#,
+                &third_party.provider as &ThirdPartySignInProvider,
+                &third_party.user_id as &ThirdPartySignInUserId,
+                player_id as &PlayerId
+            )
+            .execute(conn)
+            .await?
+        }
+        SignInMethod::EmailPassword(email_password) => {
+            query!(
+                r#"
+                insert into email_password_sign_in_method (email, password_hash, player_id)
+                values ($1, $2, $3)
+                "#,
+                &email_password.email as &Email,
+                &email_password.password_hash as &PasswordHash,
+                player_id as &PlayerId
+            )
+            .execute(conn)
+            .await?
+        }
+        SignInMethod::Anonymous { device_id } => {
+            query!(
+                r#"
+                insert into anonymous_sign_in_method (device_id, player_id)
+                values ($1, $2)
+                "#,
+                device_id as &DeviceId,
+                player_id as &PlayerId
+            )
+            .execute(conn)
+            .await?
+        }
+    };
+
+    Ok(())
+}
+
+/// Whether the given [sqlx::Error] is a unique constraint violation.
+fn is_unique_violation(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .is_some_and(|e| e.is_unique_violation())
+}
+
+/// Whether the given [sqlx::Error] is a unique violation of the given constraint.
+fn is_unique_violation_of(e: &sqlx::Error, constraint: &str) -> bool {
+    e.as_database_error()
+        .is_some_and(|e| e.is_unique_violation() && e.constraint() == Some(constraint))
+}
+
+/// [PlayersDb] wrapper logging queries slower than the configured threshold.
+#[derive(Clone)]
+pub struct SlowQueryLoggingPlayersDb<D = PgPool> {
+    /// Wrapped [PlayersDb].
+    inner: D,
+
+    /// Queries taking longer than this are logged.
+    slow_query_threshold: Duration,
+}
+
+impl<D> SlowQueryLoggingPlayersDb<D> {
+    /// Creates a new [SlowQueryLoggingPlayersDb] wrapping the given [PlayersDb].
+    pub fn new(inner: D, slow_query_threshold: Duration) -> SlowQueryLoggingPlayersDb<D> {
+        SlowQueryLoggingPlayersDb {
+            inner,
+            slow_query_threshold,
+        }
+    }
+}
+
+impl<D: PlayersDb + Sync> PlayersDb for SlowQueryLoggingPlayersDb<D> {
+    async fn create_player_with_sign_in_method(
+        &self,
+        player: &Player,
+        sign_in_method: &SignInMethod,
+    ) -> Result<Player, PlayersError> {
+        log_if_slow(
+            "create_player_with_sign_in_method",
+            self.slow_query_threshold,
+            self.inner
+                .create_player_with_sign_in_method(player, sign_in_method),
+        )
+        .await
+    }
+
+    async fn find_player_with_sign_in_method(
+        &self,
+        sign_in_method: &SignInMethod,
+    ) -> Result<Player, PlayersError> {
+        log_if_slow(
+            "find_player_with_sign_in_method",
+            self.slow_query_threshold,
+            self.inner.find_player_with_sign_in_method(sign_in_method),
+        )
+        .await
+    }
+
+    async fn add_sign_in_method(
+        &self,
+        player_id: &PlayerId,
+        sign_in_method: &SignInMethod,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "add_sign_in_method",
+            self.slow_query_threshold,
+            self.inner.add_sign_in_method(player_id, sign_in_method),
+        )
+        .await
+    }
+
+    async fn find_sign_in_methods(
+        &self,
+        player_id: &PlayerId,
+    ) -> Result<Vec<LinkedSignInMethod>, PlayersError> {
+        log_if_slow(
+            "find_sign_in_methods",
+            self.slow_query_threshold,
+            self.inner.find_sign_in_methods(player_id),
+        )
+        .await
+    }
+
+    async fn find_player_by_id(&self, player_id: &PlayerId) -> Result<Player, PlayersError> {
+        log_if_slow(
+            "find_player_by_id",
+            self.slow_query_threshold,
+            self.inner.find_player_by_id(player_id),
+        )
+        .await
+    }
+
+    async fn find_players_by_ids(
+        &self,
+        player_ids: &[PlayerId],
+    ) -> Result<Vec<Player>, PlayersError> {
+        log_if_slow(
+            "find_players_by_ids",
+            self.slow_query_threshold,
+            self.inner.find_players_by_ids(player_ids),
+        )
+        .await
+    }
+
+    async fn list_players(
+        &self,
+        after: Option<PlayerId>,
+        limit: u16,
+    ) -> Result<Page<Player>, PlayersError> {
+        log_if_slow(
+            "list_players",
+            self.slow_query_threshold,
+            self.inner.list_players(after, limit),
+        )
+        .await
+    }
+
+    async fn touch_last_seen(&self, player_id: &PlayerId) -> Result<(), PlayersError> {
+        log_if_slow(
+            "touch_last_seen",
+            self.slow_query_threshold,
+            self.inner.touch_last_seen(player_id),
+        )
+        .await
+    }
+
+    async fn count_players_seen_since(&self, since: DateTime<Utc>) -> Result<i64, PlayersError> {
+        log_if_slow(
+            "count_players_seen_since",
+            self.slow_query_threshold,
+            self.inner.count_players_seen_since(since),
+        )
+        .await
+    }
+
+    async fn set_banned_until(
+        &self,
+        player_id: &PlayerId,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "set_banned_until",
+            self.slow_query_threshold,
+            self.inner.set_banned_until(player_id, until),
+        )
+        .await
+    }
+
+    async fn get_preferences(
+        &self,
+        player_id: &PlayerId,
+    ) -> Result<PlayerPreferences, PlayersError> {
+        log_if_slow(
+            "get_preferences",
+            self.slow_query_threshold,
+            self.inner.get_preferences(player_id),
+        )
+        .await
+    }
+
+    async fn set_preferences(
+        &self,
+        player_id: &PlayerId,
+        preferences: &PlayerPreferences,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "set_preferences",
+            self.slow_query_threshold,
+            self.inner.set_preferences(player_id, preferences),
+        )
+        .await
+    }
+
+    async fn delete_player(&self, player_id: &PlayerId) -> Result<(), PlayersError> {
+        log_if_slow(
+            "delete_player",
+            self.slow_query_threshold,
+            self.inner.delete_player(player_id),
+        )
+        .await
+    }
+
+    async fn screen_name_exists(
+        &self,
+        screen_name: &PlayerScreenName,
+    ) -> Result<bool, PlayersError> {
+        log_if_slow(
+            "screen_name_exists",
+            self.slow_query_threshold,
+            self.inner.screen_name_exists(screen_name),
+        )
+        .await
+    }
+
+    async fn find_player_with_email(
+        &self,
+        email: &Email,
+    ) -> Result<(Player, PasswordHash), PlayersError> {
+        log_if_slow(
+            "find_player_with_email",
+            self.slow_query_threshold,
+            self.inner.find_player_with_email(email),
+        )
+        .await
+    }
+
+    async fn create_refresh_token(
+        &self,
+        refresh_token: &StoredRefreshToken,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "create_refresh_token",
+            self.slow_query_threshold,
+            self.inner.create_refresh_token(refresh_token),
+        )
+        .await
+    }
+
+    async fn find_refresh_token(
+        &self,
+        hash: &RefreshTokenHash,
+    ) -> Result<StoredRefreshToken, PlayersError> {
+        log_if_slow(
+            "find_refresh_token",
+            self.slow_query_threshold,
+            self.inner.find_refresh_token(hash),
+        )
+        .await
+    }
+
+    async fn rotate_refresh_token(&self, hash: &RefreshTokenHash) -> Result<bool, PlayersError> {
+        log_if_slow(
+            "rotate_refresh_token",
+            self.slow_query_threshold,
+            self.inner.rotate_refresh_token(hash),
+        )
+        .await
+    }
+
+    async fn revoke_refresh_token_family(
+        &self,
+        family_id: &RefreshTokenFamilyId,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "revoke_refresh_token_family",
+            self.slow_query_threshold,
+            self.inner.revoke_refresh_token_family(family_id),
+        )
+        .await
+    }
+
+    async fn revoke_token(
+        &self,
+        jti: &AuthTokenId,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "revoke_token",
+            self.slow_query_threshold,
+            self.inner.revoke_token(jti, expires_at),
+        )
+        .await
+    }
+
+    async fn is_token_revoked(&self, jti: &AuthTokenId) -> Result<bool, PlayersError> {
+        log_if_slow(
+            "is_token_revoked",
+            self.slow_query_threshold,
+            self.inner.is_token_revoked(jti),
+        )
+        .await
+    }
+    async fn create_notification(
+        &self,
+        player_id: &PlayerId,
+        notification: &Notification,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "create_notification",
+            self.slow_query_threshold,
+            self.inner.create_notification(player_id, notification),
+        )
+        .await
+    }
+
+    async fn find_notifications(
+        &self,
+        player_id: &PlayerId,
+        unread_only: bool,
+        before: Option<NotificationId>,
+        limit: i64,
+    ) -> Result<Vec<Notification>, PlayersError> {
+        log_if_slow(
+            "find_notifications",
+            self.slow_query_threshold,
+            self.inner
+                .find_notifications(player_id, unread_only, before, limit),
+        )
+        .await
+    }
+
+    async fn mark_notifications_read(
+        &self,
+        player_id: &PlayerId,
+        ids: &[NotificationId],
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "mark_notifications_read",
+            self.slow_query_threshold,
+            self.inner.mark_notifications_read(player_id, ids),
+        )
+        .await
+    }
+
+    async fn find_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<Option<Friendship>, PlayersError> {
+        log_if_slow(
+            "find_friendship",
+            self.slow_query_threshold,
+            self.inner.find_friendship(player_id, other_id),
+        )
+        .await
+    }
+
+    async fn create_friendship(&self, friendship: &Friendship) -> Result<(), PlayersError> {
+        log_if_slow(
+            "create_friendship",
+            self.slow_query_threshold,
+            self.inner.create_friendship(friendship),
+        )
+        .await
+    }
+
+    async fn set_friendship_state(
+        &self,
+        requester_id: &PlayerId,
+        addressee_id: &PlayerId,
+        state: FriendshipState,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "set_friendship_state",
+            self.slow_query_threshold,
+            self.inner
+                .set_friendship_state(requester_id, addressee_id, state),
+        )
+        .await
+    }
+
+    async fn delete_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "delete_friendship",
+            self.slow_query_threshold,
+            self.inner.delete_friendship(player_id, other_id),
+        )
+        .await
+    }
+
+    async fn block_player(
+        &self,
+        blocker_id: &PlayerId,
+        blocked_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "block_player",
+            self.slow_query_threshold,
+            self.inner.block_player(blocker_id, blocked_id),
+        )
+        .await
+    }
+
+    async fn unblock_player(
+        &self,
+        blocker_id: &PlayerId,
+        blocked_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        log_if_slow(
+            "unblock_player",
+            self.slow_query_threshold,
+            self.inner.unblock_player(blocker_id, blocked_id),
+        )
+        .await
+    }
+
+    async fn find_friends(&self, player_id: &PlayerId) -> Result<Vec<Friend>, PlayersError> {
+        log_if_slow(
+            "find_friends",
+            self.slow_query_threshold,
+            self.inner.find_friends(player_id),
+        )
+        .await
+    }
+
+    async fn create_report(&self, report: &Report) -> Result<(), PlayersError> {
+        log_if_slow(
+            "create_report",
+            self.slow_query_threshold,
+            self.inner.create_report(report),
+        )
+        .await
+    }
+
+    async fn list_open_reports(
+        &self,
+        after: Option<ReportId>,
+        limit: u16,
+    ) -> Result<Page<Report>, PlayersError> {
+        log_if_slow(
+            "list_open_reports",
+            self.slow_query_threshold,
+            self.inner.list_open_reports(after, limit),
+        )
+        .await
+    }
+
+    async fn resolve_report(&self, report_id: &ReportId) -> Result<(), PlayersError> {
+        log_if_slow(
+            "resolve_report",
+            self.slow_query_threshold,
+            self.inner.resolve_report(report_id),
+        )
+        .await
+    }
+}
+
+/// Verifies that the `player.screen_name` column size matches [PlayerScreenName::MAX_SIZE], so
+/// the Rust validation and the database schema can't silently diverge. The
+/// `player_screen_name_size` check constraint is covered by a database test instead.
+pub async fn verify_screen_name_column(pg_pool: &PgPool) -> anyhow::Result<()> {
+    let column_size = query_scalar!(
+        r#"
+        select character_maximum_length
+        from information_schema.columns
+        where table_name = 'player' and column_name = 'screen_name'
+        "#
+    )
+    .fetch_one(pg_pool)
+    .await
+    .context("fetch player.screen_name column size")?;
+
+    anyhow::ensure!(
+        column_size == Some(PlayerScreenName::MAX_SIZE as i32),
+        "player.screen_name column size {column_size:?} does not match PlayerScreenName::MAX_SIZE {}",
+        PlayerScreenName::MAX_SIZE
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slow_query_log::capture_logs;
+    use mockall::predicate::eq;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn slow_query_logging_players_db_warns_about_slow_queries() {
+        let mut inner = MockPlayersDb::new();
+        inner
+            .expect_find_player_by_id()
+            .with(eq(PlayerId::test()))
+            .returning(|_| {
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    Ok(Player::test())
+                })
+            });
+        let players_db = SlowQueryLoggingPlayersDb::new(inner, Duration::from_millis(1));
+        let (logs, _guard) = capture_logs();
+
+        let player = players_db.find_player_by_id(&PlayerId::test()).await;
+
+        assert_eq!(player.unwrap(), Player::test());
+        let logs = logs.contents();
+        assert!(logs.contains("slow query"));
+        assert!(logs.contains("find_player_by_id"));
+        assert!(!logs.contains(&PlayerId::test().0.to_string()));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn create_player_with_sign_in_method_retries_taken_screen_name(pg_pool: PgPool) {
+        let first = Player::test();
+        let second = Player {
+            id: PlayerId::random(),
+            screen_name: PlayerScreenName::from_str("TEST-Screen-Name").unwrap(),
+            ..Player::test()
+        };
+
+        pg_pool
+            .create_player_with_sign_in_method(&first, &SignInMethod::test_google())
+            .await
+            .unwrap();
+        let created = pg_pool
+            .create_player_with_sign_in_method(&second, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+
+        assert_eq!(created.id, second.id);
+        assert_ne!(created.screen_name, second.screen_name);
+        assert_eq!(
+            pg_pool.find_player_by_id(&second.id).await.unwrap(),
+            created
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn find_players_by_ids_finds_multiple_players(pg_pool: PgPool) {
+        let first = Player::test();
+        let second = Player {
+            id: PlayerId::random(),
+            screen_name: PlayerScreenName::from_str("second-screen-name").unwrap(),
+            ..Player::test()
+        };
+        pg_pool
+            .create_player_with_sign_in_method(&first, &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&second, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+
+        let players = pg_pool
+            .find_players_by_ids(&[second.id, PlayerId::random(), first.id])
+            .await
+            .unwrap();
+
+        assert_eq!(players, vec![first, second]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn list_players_pages_through_all_players(pg_pool: PgPool) {
+        let mut players = Vec::new();
+        for i in 0..3 {
+            let player = Player {
+                joined_at: PlayerJoinedAt::test(),
+                ..Player::random()
+            };
+            let sign_in_method = SignInMethod::Anonymous {
+                device_id: DeviceId(format!("device-{i}")),
+            };
+            let player = pg_pool
+                .create_player_with_sign_in_method(&player, &sign_in_method)
+                .await
+                .unwrap();
+            players.push(player);
+        }
+
+        let first = pg_pool.list_players(None, 2).await.unwrap();
+        let second = pg_pool.list_players(first.next_cursor, 2).await.unwrap();
+
+        assert_eq!(first.items, players[..2]);
+        assert_eq!(first.next_cursor, Some(players[1].id));
+        assert_eq!(second.items, players[2..]);
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn touch_last_seen_is_throttled(pg_pool: PgPool) {
+        let player = Player::test();
+        pg_pool
+            .create_player_with_sign_in_method(&player, &SignInMethod::test_google())
+            .await
+            .unwrap();
+        assert_eq!(
+            pg_pool
+                .find_player_by_id(&player.id)
+                .await
+                .unwrap()
+                .last_seen_at,
+            None
+        );
+
+        pg_pool.touch_last_seen(&player.id).await.unwrap();
+        let first = pg_pool.find_player_by_id(&player.id).await.unwrap();
+        pg_pool.touch_last_seen(&player.id).await.unwrap();
+        let second = pg_pool.find_player_by_id(&player.id).await.unwrap();
+
+        assert!(first.last_seen_at.is_some());
+        assert_eq!(second.last_seen_at, first.last_seen_at);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn preferences_default_until_set(pg_pool: PgPool) {
+        let player = Player::test();
+        pg_pool
+            .create_player_with_sign_in_method(&player, &SignInMethod::test_google())
+            .await
+            .unwrap();
+
+        let default = pg_pool.get_preferences(&player.id).await.unwrap();
+        pg_pool
+            .set_preferences(&player.id, &PlayerPreferences::test())
+            .await
+            .unwrap();
+        let updated = pg_pool.get_preferences(&player.id).await.unwrap();
+
+        assert_eq!(default, PlayerPreferences::default());
+        assert_eq!(updated, PlayerPreferences::test());
+        assert!(matches!(
+            pg_pool
+                .set_preferences(&PlayerId::random(), &PlayerPreferences::test())
+                .await,
+            Err(PlayersError::PlayerNotFound)
+        ));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn set_banned_until_bans_and_lifts_ban(pg_pool: PgPool) {
+        let player = Player::test();
+        let until = DateTime::from_timestamp(4102444800, 0).unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&player, &SignInMethod::test_google())
+            .await
+            .unwrap();
+
+        pg_pool
+            .set_banned_until(&player.id, Some(until))
+            .await
+            .unwrap();
+        let banned = pg_pool.find_player_by_id(&player.id).await.unwrap();
+        pg_pool.set_banned_until(&player.id, None).await.unwrap();
+        let lifted = pg_pool.find_player_by_id(&player.id).await.unwrap();
+
+        assert_eq!(banned.banned_until, Some(until));
+        assert_eq!(lifted.banned_until, None);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn friendship_request_accept_flow(pg_pool: PgPool) {
+        let requester = Player::test();
+        let addressee = Player {
+            id: Friendship::test().addressee_id,
+            screen_name: PlayerScreenName::from_str("addressee").unwrap(),
+            ..Player::test()
+        };
+        pg_pool
+            .create_player_with_sign_in_method(&requester, &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&addressee, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+
+        pg_pool
+            .create_friendship(&Friendship::test())
+            .await
+            .unwrap();
+        pg_pool
+            .create_friendship(&Friendship {
+                requester_id: addressee.id,
+                addressee_id: requester.id,
+                state: FriendshipState::Pending,
+            })
+            .await
+            .unwrap();
+        let pending = pg_pool.find_friends(&addressee.id).await.unwrap();
+        pg_pool
+            .set_friendship_state(&requester.id, &addressee.id, FriendshipState::Accepted)
+            .await
+            .unwrap();
+        let accepted = pg_pool.find_friends(&requester.id).await.unwrap();
+
+        assert_eq!(
+            pending,
+            vec![Friend {
+                player: PublicPlayer::from(requester.clone()),
+                state: FriendshipState::Pending,
+                incoming: true,
+            }]
+        );
+        assert_eq!(
+            accepted,
+            vec![Friend {
+                player: PublicPlayer::from(addressee.clone()),
+                state: FriendshipState::Accepted,
+                incoming: false,
+            }]
+        );
+        assert_eq!(
+            pg_pool
+                .find_friendship(&addressee.id, &requester.id)
+                .await
+                .unwrap(),
+            Some(Friendship {
+                state: FriendshipState::Accepted,
+                ..Friendship::test()
+            })
+        );
+
+        pg_pool
+            .delete_friendship(&addressee.id, &requester.id)
+            .await
+            .unwrap();
+        assert_eq!(pg_pool.find_friends(&requester.id).await.unwrap(), vec![]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn block_player_replaces_pending_friend_request(pg_pool: PgPool) {
+        let friendship = Friendship::test();
+        let requester = Player::test();
+        let addressee = Player {
+            id: friendship.addressee_id,
+            screen_name: PlayerScreenName::from_str("addressee").unwrap(),
+            ..Player::test()
+        };
+        pg_pool
+            .create_player_with_sign_in_method(&requester, &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&addressee, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+        pg_pool.create_friendship(&friendship).await.unwrap();
+
+        pg_pool
+            .block_player(&addressee.id, &requester.id)
+            .await
+            .unwrap();
+        let blocked = pg_pool
+            .find_friendship(&requester.id, &addressee.id)
+            .await
+            .unwrap();
+        pg_pool
+            .block_player(&requester.id, &addressee.id)
+            .await
+            .unwrap();
+        let blocked_back = pg_pool
+            .find_friendship(&requester.id, &addressee.id)
+            .await
+            .unwrap();
+        pg_pool
+            .unblock_player(&addressee.id, &requester.id)
+            .await
+            .unwrap();
+        let unblocked_by_addressee = pg_pool
+            .find_friendship(&requester.id, &addressee.id)
+            .await
+            .unwrap();
+
+        let blocked_by_addressee = Some(Friendship {
+            requester_id: addressee.id,
+            addressee_id: requester.id,
+            state: FriendshipState::Blocked,
+        });
+        assert_eq!(blocked, blocked_by_addressee);
+        assert_eq!(blocked_back, blocked_by_addressee);
+        assert_eq!(
+            unblocked_by_addressee,
+            Some(Friendship {
+                requester_id: requester.id,
+                addressee_id: addressee.id,
+                state: FriendshipState::Blocked,
+            })
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn mutual_blocks_are_kept_until_each_is_unblocked(pg_pool: PgPool) {
+        let player = Player::test();
+        let other = Player {
+            id: Friendship::test().addressee_id,
+            screen_name: PlayerScreenName::from_str("other").unwrap(),
+            ..Player::test()
+        };
+        pg_pool
+            .create_player_with_sign_in_method(&player, &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&other, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+
+        pg_pool.block_player(&player.id, &other.id).await.unwrap();
+        pg_pool.block_player(&other.id, &player.id).await.unwrap();
+        pg_pool.unblock_player(&player.id, &other.id).await.unwrap();
+        let blocked_by_other = pg_pool
+            .find_friendship(&player.id, &other.id)
+            .await
+            .unwrap();
+        pg_pool.unblock_player(&other.id, &player.id).await.unwrap();
+        let unblocked = pg_pool
+            .find_friendship(&player.id, &other.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            blocked_by_other,
+            Some(Friendship {
+                requester_id: other.id,
+                addressee_id: player.id,
+                state: FriendshipState::Blocked,
+            })
+        );
+        assert_eq!(unblocked, None);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn screen_name_constraints_match_player_screen_name_sizes(pg_pool: PgPool) {
+        pg_pool
+            .create_player_with_sign_in_method(&Player::test(), &SignInMethod::test_google())
+            .await
+            .unwrap();
+        let set_screen_name = |size: usize| {
+            sqlx::query("update player set screen_name = $1 where id = $2")
+                .bind("w".repeat(size))
+                .bind(PlayerId::test().0)
+                .execute(&pg_pool)
+        };
+
+        let below_min = set_screen_name(PlayerScreenName::MIN_SIZE - 1).await;
+        let min = set_screen_name(PlayerScreenName::MIN_SIZE).await;
+        let max = set_screen_name(PlayerScreenName::MAX_SIZE).await;
+        let above_max = set_screen_name(PlayerScreenName::MAX_SIZE + 1).await;
+
+        assert!(below_min.is_err());
+        assert!(min.is_ok());
+        assert!(max.is_ok());
+        assert!(above_max.is_err());
+        verify_screen_name_column(&pg_pool).await.unwrap();
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn report_is_listed_until_resolved(pg_pool: PgPool) {
+        let report = Report::test();
+        let reported = Player {
+            id: report.reported_id,
+            screen_name: PlayerScreenName::from_str("reported").unwrap(),
+            ..Player::test()
+        };
+        pg_pool
+            .create_player_with_sign_in_method(&Player::test(), &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&reported, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+
+        pg_pool.create_report(&report).await.unwrap();
+        let open = pg_pool.list_open_reports(None, 10).await.unwrap();
+        pg_pool.resolve_report(&report.id).await.unwrap();
+        let resolved = pg_pool.list_open_reports(None, 10).await.unwrap();
+        let unknown = pg_pool.resolve_report(&ReportId::random()).await;
+
+        assert_eq!(open.items, vec![report]);
+        assert_eq!(resolved.items, vec![]);
+        assert!(matches!(unknown, Err(PlayersError::ReportNotFound)));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn concurrent_reports_are_rate_limited(pg_pool: PgPool) {
+        let reported = Player {
+            id: Report::test().reported_id,
+            screen_name: PlayerScreenName::from_str("reported").unwrap(),
+            ..Player::test()
+        };
+        pg_pool
+            .create_player_with_sign_in_method(&Player::test(), &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&reported, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+
+        let attempts = (0..Report::MAX_PER_WINDOW * 2)
+            .map(|_| {
+                let pg_pool = pg_pool.clone();
+                tokio::spawn(async move {
+                    let report = Report {
+                        id: ReportId::random(),
+                        ..Report::test()
+                    };
+                    pg_pool.create_report(&report).await
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut created = 0;
+        let mut rate_limited = 0;
+        for attempt in attempts {
+            match attempt.await.unwrap() {
+                Ok(()) => created += 1,
+                Err(PlayersError::ReportRateLimited) => rate_limited += 1,
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        }
+
+        assert_eq!(created, Report::MAX_PER_WINDOW);
+        assert_eq!(rate_limited, Report::MAX_PER_WINDOW);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn reports_are_kept_when_players_delete_their_accounts(pg_pool: PgPool) {
+        let reported = Player {
+            id: Report::test().reported_id,
+            screen_name: PlayerScreenName::from_str("reported").unwrap(),
+            ..Player::test()
+        };
+        let by_deleted = Report {
+            game_id: Some(uuid::Uuid::new_v4()),
+            ..Report::test()
+        };
+        let of_deleted = Report {
+            id: ReportId::random(),
+            reporter_id: Some(reported.id),
+            reported_id: PlayerId::test(),
+            ..Report::test()
+        };
+        pg_pool
+            .create_player_with_sign_in_method(&Player::test(), &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .create_player_with_sign_in_method(&reported, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+        pg_pool.create_report(&by_deleted).await.unwrap();
+        pg_pool.create_report(&of_deleted).await.unwrap();
+
+        pg_pool.delete_player(&PlayerId::test()).await.unwrap();
+        let open = pg_pool.list_open_reports(None, 10).await.unwrap();
+
+        assert_eq!(open.items.len(), 2);
+        assert!(open.items.contains(&Report {
+            reporter_id: None,
+            ..by_deleted
+        }));
+        assert!(open.items.contains(&of_deleted));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn guests_on_same_device_are_different_players_not_found_by_device_id(pg_pool: PgPool) {
+        let first = Player::test();
+        let second = Player::random();
+        for player in [&first, &second] {
+            pg_pool
+                .create_player_with_sign_in_method(player, &SignInMethod::test_anonymous())
+                .await
+                .unwrap();
+        }
+
+        let found = pg_pool
+            .find_player_with_sign_in_method(&SignInMethod::test_anonymous())
+            .await;
+
+        assert!(matches!(found, Err(PlayersError::PlayerNotFound)));
+        for player in [&first, &second] {
+            assert_eq!(
+                pg_pool.find_sign_in_methods(&player.id).await.unwrap(),
+                vec![LinkedSignInMethod::Anonymous {
+                    device_id: DeviceId::test(),
+                }]
+            );
+        }
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn add_sign_in_method_removes_anonymous_sign_in_method(pg_pool: PgPool) {
+        let player = Player::test();
+        pg_pool
+            .create_player_with_sign_in_method(&player, &SignInMethod::test_anonymous())
+            .await
+            .unwrap();
+
+        pg_pool
+            .add_sign_in_method(&player.id, &SignInMethod::test_google())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            pg_pool.find_sign_in_methods(&player.id).await.unwrap(),
+            vec![LinkedSignInMethod::ThirdParty {
+                provider: ThirdPartySignInProvider::Google,
+            }]
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn count_players_seen_since_counts_recently_seen_players(pg_pool: PgPool) {
+        let seen = Player::test();
+        let never_seen = Player::random();
+        for player in [&seen, &never_seen] {
+            pg_pool
+                .create_player_with_sign_in_method(player, &SignInMethod::test_anonymous())
+                .await
+                .unwrap();
+        }
+        let before = Utc::now() - TimeDelta::seconds(1);
+        pg_pool.touch_last_seen(&seen.id).await.unwrap();
+
+        let recently = pg_pool.count_players_seen_since(before).await.unwrap();
+        let later = pg_pool
+            .count_players_seen_since(Utc::now() + TimeDelta::seconds(1))
+            .await
+            .unwrap();
+
+        assert_eq!(recently, 1);
+        assert_eq!(later, 0);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a Postgres database at DATABASE_URL"]
+    async fn delete_player_deletes_player_with_sign_in_methods(pg_pool: PgPool) {
+        let player = Player::test();
+        let email_password = SignInMethod::EmailPassword(EmailPasswordSignInMethod {
+            email: Email::test(),
+            password_hash: PasswordHash::dummy().clone(),
+        });
+        pg_pool
+            .create_player_with_sign_in_method(&player, &SignInMethod::test_google())
+            .await
+            .unwrap();
+        pg_pool
+            .add_sign_in_method(&player.id, &email_password)
+            .await
+            .unwrap();
+        pg_pool
+            .create_refresh_token(&StoredRefreshToken::test())
+            .await
+            .unwrap();
+        assert_eq!(
+            pg_pool.find_sign_in_methods(&player.id).await.unwrap(),
+            vec![
+                LinkedSignInMethod::ThirdParty {
+                    provider: ThirdPartySignInProvider::Google,
+                },
+                LinkedSignInMethod::EmailPassword {
+                    email: Email::test(),
+                },
+            ]
+        );
+
+        pg_pool.delete_player(&player.id).await.unwrap();
+
+        let counts = query!(
+            r#"
+            select
+                (select count(*) from player) as "players!",
+                (select count(*) from third_party_sign_in_method) as "third_party!",
+                (select count(*) from email_password_sign_in_method) as "email_password!",
+                (select count(*) from refresh_token) as "refresh_tokens!"
+            "#
+        )
+        .fetch_one(&pg_pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            (
+                counts.players,
+                counts.third_party,
+                counts.email_password,
+                counts.refresh_tokens
+            ),
+            (0, 0, 0, 0)
+        );
+        assert!(matches!(
+            pg_pool.delete_player(&player.id).await,
+            Err(PlayersError::PlayerNotFound)
+        ));
+    }
+
+    #[test]
+    fn migrations_match_player_screen_name_sizes() {
+        let initial_migration = include_str!("../../migrations/00001_initial_migration.sql");
+        assert!(initial_migration.contains(&format!(
+            "screen_name varchar({}) not null",
+            PlayerScreenName::MAX_SIZE
+        )));
+
+        let size_check_migration =
+            include_str!("../../migrations/00009_player_screen_name_char_length_check.sql");
+        assert!(size_check_migration.contains(&format!(
+            "check (char_length(screen_name) between {} and {})",
+            PlayerScreenName::MIN_SIZE,
+            PlayerScreenName::MAX_SIZE
+        )));
+    }
 }
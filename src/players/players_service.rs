@@ -1,58 +1,305 @@
+use crate::config::{
+    IdTokenPlatformAudiencesConfig, IdTokenReplayProtectionConfig, IdTokenVerificationLimitConfig,
+};
 use crate::players::error::*;
+use crate::players::friendship::*;
 use crate::players::http::*;
+use crate::players::id_token_replay_guard::*;
+use crate::players::id_token_verification_limit::*;
 use crate::players::jwt_service::*;
+use crate::players::notification::*;
+use crate::players::page::*;
+use crate::players::password::*;
 use crate::players::player::*;
 use crate::players::players_db::*;
+use crate::players::preferences::*;
+use crate::players::refresh_token::*;
+use crate::players::report::*;
 use crate::players::sign_in_method::*;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use id_token_verifier::util::OneOrVec;
+use id_token_verifier::validation::Aud;
 use id_token_verifier::*;
 use serde::*;
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::warn;
 
 /// Provides logic working with [Player]s.
 #[cfg_attr(test, mockall::automock)]
 pub trait PlayersService {
-    /// Handles the given [SignInRequest].
+    /// Handles the given [SignInRequest]. Fails with [PlayersError::PlayerBanned] if the [Player]
+    /// is banned.
     fn sign_in(
         &self,
         request: &SignInRequest,
-    ) -> impl Future<Output = Result<AuthToken<'static>, PlayersError>> + Send;
+    ) -> impl Future<Output = Result<SignedIn, PlayersError>> + Send;
+
+    /// Exchanges the given [RefreshToken] for a new [TokenPair], rotating the [RefreshToken].
+    /// Reusing an already rotated [RefreshToken] revokes its whole chain. Fails with
+    /// [PlayersError::PlayerBanned] if the [Player] is banned.
+    fn refresh(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> impl Future<Output = Result<TokenPair, PlayersError>> + Send;
+
+    /// Links the sign in method from the given [LinkSignInMethodRequest] to the [Player] with the
+    /// given [PlayerId], e.g. to upgrade a guest to a Google account keeping their progress.
+    fn link_sign_in_method(
+        &self,
+        player_id: &PlayerId,
+        request: &LinkSignInMethodRequest,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Returns the [LinkedSignInMethod]s of the [Player] with the given [PlayerId].
+    fn sign_in_methods(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<Vec<LinkedSignInMethod>, PlayersError>> + Send;
 
     /// Returns a [Player] by the given [PlayerId].
     fn player_by_id(
         &self,
         player_id: &PlayerId,
     ) -> impl Future<Output = Result<Player, PlayersError>> + Send;
+
+    /// Returns the [Player]s with the given [PlayerId]s, skipping unknown ones.
+    fn players_by_ids(
+        &self,
+        player_ids: &[PlayerId],
+    ) -> impl Future<Output = Result<Vec<Player>, PlayersError>> + Send;
+
+    /// Returns a [Page] of [Player]s matching the given [PlayersQuery].
+    fn list_players(
+        &self,
+        query: &PlayersQuery,
+    ) -> impl Future<Output = Result<Page<Player>, PlayersError>> + Send;
+
+    /// Records that the given [Player] has been seen now, at most once per [LAST_SEEN_THROTTLE].
+    fn touch_last_seen(
+        &self,
+        player: &Player,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Bans the [Player] with the given [PlayerId] until the given time, [None] lifts the ban.
+    fn ban_player(
+        &self,
+        player_id: &PlayerId,
+        until: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Requests friendship of the [Player] with the given [PlayerId] with the other given one.
+    /// Requesting an already requested or accepted [Friendship] does nothing, requesting one
+    /// the other [Player] has requested already accepts it.
+    fn request_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Accepts the friend request sent by the [Player] with the given requester [PlayerId] to the
+    /// one with the given [PlayerId].
+    fn accept_friendship(
+        &self,
+        player_id: &PlayerId,
+        requester_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Removes the [Friendship] between the [Player]s with the given [PlayerId]s, declining or
+    /// withdrawing it if it is still pending.
+    fn remove_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Blocks the other given [Player] for the [Player] with the given [PlayerId], removing any
+    /// [Friendship] between them.
+    fn block_player(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Lifts the block of the other given [Player] by the [Player] with the given [PlayerId].
+    fn unblock_player(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Returns the [Friend]s of the [Player] with the given [PlayerId].
+    fn friends(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<Vec<Friend>, PlayersError>> + Send;
+
+    /// Reports the [Player] with the [ReportRequest::reported_id] on behalf of the [Player] with
+    /// the given [PlayerId], at most [Report::MAX_PER_WINDOW] times per
+    /// [Report::RATE_LIMIT_WINDOW].
+    fn report_player(
+        &self,
+        reporter_id: &PlayerId,
+        request: &ReportRequest,
+    ) -> impl Future<Output = Result<Report, PlayersError>> + Send;
+
+    /// Returns a [Page] of the [ReportStatus::Open] [Report]s matching the given [ReportsQuery].
+    fn open_reports(
+        &self,
+        query: &ReportsQuery,
+    ) -> impl Future<Output = Result<Page<Report>, PlayersError>> + Send;
+
+    /// Resolves the [Report] with the given [ReportId].
+    fn resolve_report(
+        &self,
+        report_id: &ReportId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Returns the [PlayerPreferences] of the [Player] with the given [PlayerId].
+    fn preferences(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<PlayerPreferences, PlayersError>> + Send;
+
+    /// Replaces the [PlayerPreferences] of the [Player] with the given [PlayerId].
+    fn set_preferences(
+        &self,
+        player_id: &PlayerId,
+        preferences: &PlayerPreferences,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Deletes the [Player] with the given [PlayerId] together with all their data.
+    fn delete_player(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+
+    /// Checks whether the given screen name is valid and not taken by any [Player], without
+    /// changing anything.
+    fn screen_name_availability(
+        &self,
+        screen_name: &str,
+    ) -> impl Future<Output = Result<ScreenNameAvailability, PlayersError>> + Send;
+
+    /// Enqueues a new [Notification] of the given [NotificationKind] with the given message to the
+    /// inbox of the [Player] with the given [PlayerId], e.g. when a friend request is sent to them.
+    fn notify(
+        &self,
+        player_id: &PlayerId,
+        kind: NotificationKind,
+        message: &str,
+    ) -> impl Future<Output = Result<Notification, PlayersError>> + Send;
+
+    /// Returns a [NotificationsPage] of the [Player] with the given [PlayerId] matching the given
+    /// [NotificationsQuery].
+    fn notifications(
+        &self,
+        player_id: &PlayerId,
+        query: &NotificationsQuery,
+    ) -> impl Future<Output = Result<NotificationsPage, PlayersError>> + Send;
+
+    /// Marks the [Notification]s with the given [NotificationId]s of the [Player] with the given
+    /// [PlayerId] as read.
+    fn mark_notifications_read(
+        &self,
+        player_id: &PlayerId,
+        ids: &[NotificationId],
+    ) -> impl Future<Output = Result<(), PlayersError>> + Send;
+}
+
+/// Outcome of a successful [SignInRequest].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedIn {
+    /// [TokenPair] of the signed in [Player].
+    pub token_pair: TokenPair,
+
+    /// The signed in [Player].
+    pub player: Player,
+
+    /// Whether the [Player] has been created by this sign in.
+    pub is_new_player: bool,
+}
+
+impl SignedIn {
+    #[cfg(test)]
+    /// Returns a test [SignedIn].
+    pub fn test() -> SignedIn {
+        SignedIn {
+            token_pair: TokenPair::test(),
+            player: Player::test(),
+            is_new_player: false,
+        }
+    }
 }
 
 /// Default [PlayersService] implementation.
-pub struct PlayersServiceDefault<D = PgPool, GV = IdTokenVerifierDefault, JS = JwtServiceDefault> {
+pub struct PlayersServiceDefault<
+    D = SlowQueryLoggingPlayersDb,
+    GV = IdTokenVerifierDefault,
+    JS = JwtServiceDefault,
+> {
     /// [PlayersDb] for [Player]s db operations.
     players_db: D,
 
-    /// [IdTokenVerifier] implementation for [ThirdPartySignInProvider::Google] ID tokens.
-    google_id_token_verifier: GV,
+    /// [IdTokenVerifier] implementations per configured [ThirdPartySignInProvider].
+    id_token_verifiers: HashMap<ThirdPartySignInProvider, GV>,
 
     /// [JwtService] implementation to work with [Player]s JWT tokens.
     jwt_service: JS,
+
+    /// [IdTokenReplayGuard] rejecting reused ID tokens, if replay protection is enabled.
+    id_token_replay_guard: Option<IdTokenReplayGuard>,
+
+    /// [IdTokenVerificationLimit] protecting ID token verifiers from floods of sign-ins.
+    id_token_verification_limit: IdTokenVerificationLimit,
+
+    /// Known ID token audiences per [ClientPlatform].
+    id_token_platform_audiences: IdTokenPlatformAudiencesConfig,
 }
 
 impl PlayersServiceDefault {
-    /// Creates a new [PlayersServiceDefault] with the given [PgPool], Google
-    /// [IdTokenVerifierConfig] and [JwtServiceDefault].
+    /// Creates a new [PlayersServiceDefault] with the given [PgPool] and its slow query
+    /// threshold, Google and optional Apple [IdTokenVerifierConfig]s, [JwtServiceDefault], [IdTokenReplayProtectionConfig] and
+    /// [IdTokenVerificationLimitConfig].
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         pg_pool: PgPool,
+        slow_query_threshold: Duration,
         http_client: reqwest::Client,
         google_id_token_verifier_config: IdTokenVerifierConfig,
+        apple_id_token_verifier_config: Option<IdTokenVerifierConfig>,
         jwt_service: JwtServiceDefault,
+        id_token_replay_protection_config: IdTokenReplayProtectionConfig,
+        id_token_verification_limit_config: IdTokenVerificationLimitConfig,
+        id_token_platform_audiences: IdTokenPlatformAudiencesConfig,
     ) -> PlayersServiceDefault {
-        let players_db = pg_pool;
-        let google_id_token_verifier =
-            IdTokenVerifierDefault::new(google_id_token_verifier_config, http_client);
+        let players_db = SlowQueryLoggingPlayersDb::new(pg_pool, slow_query_threshold);
+        let mut id_token_verifiers = HashMap::from([(
+            ThirdPartySignInProvider::Google,
+            IdTokenVerifierDefault::new(google_id_token_verifier_config, http_client.clone()),
+        )]);
+        if let Some(apple_id_token_verifier_config) = apple_id_token_verifier_config {
+            id_token_verifiers.insert(
+                ThirdPartySignInProvider::Apple,
+                IdTokenVerifierDefault::new(apple_id_token_verifier_config, http_client),
+            );
+        }
+        let id_token_replay_guard = id_token_replay_protection_config
+            .enabled
+            .then(|| IdTokenReplayGuard::new(id_token_replay_protection_config));
+        let id_token_verification_limit =
+            IdTokenVerificationLimit::new(id_token_verification_limit_config);
 
         PlayersServiceDefault {
             players_db,
-            google_id_token_verifier,
+            id_token_verifiers,
             jwt_service,
+            id_token_replay_guard,
+            id_token_verification_limit,
+            id_token_platform_audiences,
         }
     }
 }
@@ -63,196 +310,794 @@ where
     GV: IdTokenVerifier + Sync,
     JS: JwtService + Sync,
 {
-    async fn sign_in(&self, request: &SignInRequest) -> Result<AuthToken<'static>, PlayersError> {
-        let sign_in_method = match request {
-            SignInRequest::Google { id_token } => {
-                let claims = self
-                    .google_id_token_verifier
-                    .verify::<ThirdPartyIdTokenClaims>(id_token.as_ref())
-                    .await?;
+    async fn sign_in(&self, request: &SignInRequest) -> Result<SignedIn, PlayersError> {
+        match request {
+            SignInRequest::Google { id_token, platform } => {
+                self.third_party_sign_in(ThirdPartySignInProvider::Google, id_token, *platform)
+                    .await
+            }
+            SignInRequest::Apple { id_token, platform } => {
+                self.third_party_sign_in(ThirdPartySignInProvider::Apple, id_token, *platform)
+                    .await
+            }
+            SignInRequest::Register { email, password } => {
+                let player = self.register(email, password).await?;
+                self.complete_sign_in(player, true).await
+            }
+            SignInRequest::EmailLogin { email, password } => {
+                let player = self.email_login(email, password).await?;
+                self.complete_sign_in(player, false).await
+            }
+            SignInRequest::Guest { device_id } => {
+                let player = self.create_guest(device_id).await?;
+                self.complete_sign_in(player, true).await
+            }
+        }
+    }
 
-                SignInMethod::ThirdParty(ThirdPartySignInMethod {
-                    provider: ThirdPartySignInProvider::Google,
-                    user_id: claims.sub,
-                })
+    async fn refresh(&self, refresh_token: &RefreshToken) -> Result<TokenPair, PlayersError> {
+        let hash = RefreshTokenHash::new(refresh_token);
+        let stored = self.players_db.find_refresh_token(&hash).await?;
+
+        if stored.revoked || stored.expires_at <= Utc::now() {
+            Err(PlayersError::RefreshTokenInvalid)?
+        }
+        if stored.rotated || !self.players_db.rotate_refresh_token(&hash).await? {
+            self.players_db
+                .revoke_refresh_token_family(&stored.family_id)
+                .await?;
+            Err(PlayersError::RefreshTokenReused)?
+        }
+
+        let player = self.players_db.find_player_by_id(&stored.player_id).await?;
+        if let Some(until) = player.active_ban() {
+            Err(PlayersError::PlayerBanned(until))?
+        }
+
+        self.create_token_pair(&player, stored.family_id).await
+    }
+
+    async fn link_sign_in_method(
+        &self,
+        player_id: &PlayerId,
+        request: &LinkSignInMethodRequest,
+    ) -> Result<(), PlayersError> {
+        let (provider, id_token) = match request {
+            LinkSignInMethodRequest::Google { id_token } => {
+                (ThirdPartySignInProvider::Google, id_token)
+            }
+            LinkSignInMethodRequest::Apple { id_token } => {
+                (ThirdPartySignInProvider::Apple, id_token)
             }
         };
+        let sign_in_method =
+            SignInMethod::ThirdParty(self.verify_id_token(provider, id_token, None).await?);
 
-        let player = match self
+        let linked = match self
             .players_db
             .find_player_with_sign_in_method(&sign_in_method)
             .await
         {
-            Ok(player) => player,
+            Ok(player) if player.id == *player_id => Ok(()),
+            Ok(_) => Err(PlayersError::SignInMethodAlreadyLinked),
             Err(PlayersError::PlayerNotFound) => {
-                let player = Player {
-                    id: PlayerId::random(),
-                    screen_name: PlayerScreenName::random(),
-                    joined_at: PlayerJoinedAt::now(),
-                };
-
                 self.players_db
-                    .create_player_with_sign_in_method(&player, &sign_in_method)
-                    .await?;
-
-                player
+                    .add_sign_in_method(player_id, &sign_in_method)
+                    .await
             }
-            e => e?,
+            Err(e) => Err(e),
         };
 
-        let auth_token = self.jwt_service.create_token(player.id)?;
+        linked.inspect_err(|_| self.forget_id_token(id_token))
+    }
 
-        Ok(auth_token)
+    async fn sign_in_methods(
+        &self,
+        player_id: &PlayerId,
+    ) -> Result<Vec<LinkedSignInMethod>, PlayersError> {
+        self.players_db.find_sign_in_methods(player_id).await
     }
 
     async fn player_by_id(&self, player_id: &PlayerId) -> Result<Player, PlayersError> {
         self.players_db.find_player_by_id(player_id).await
     }
-}
-
-/// Target ID token claims. Used with [IdTokenVerifier::verify] when signing in with third party
-/// sign in providers.
-#[derive(Deserialize, Serialize)]
-struct ThirdPartyIdTokenClaims {
-    /// Subject [ThirdPartySignInUserId] the ID token is issued for.
-    sub: ThirdPartySignInUserId,
-}
 
-impl ThirdPartyIdTokenClaims {
-    #[cfg(test)]
-    /// Returns a test [ThirdPartyIdTokenClaims].
-    fn test() -> ThirdPartyIdTokenClaims {
-        ThirdPartyIdTokenClaims {
-            sub: ThirdPartySignInUserId::test(),
-        }
+    async fn players_by_ids(&self, player_ids: &[PlayerId]) -> Result<Vec<Player>, PlayersError> {
+        self.players_db.find_players_by_ids(player_ids).await
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use id_token_verifier::validation::ValidationError;
-    use mockall::mock;
-    use mockall::predicate::eq;
-    use serde::de::DeserializeOwned;
 
-    mock! {
-        IdTokenVerifier {
-            fn verify<T: DeserializeOwned + 'static>(
-                &self,
-                id_token: &str
-            ) -> impl Future<Output = Result<T, IdTokenVerifierError>> + Send;
-        }
+    async fn list_players(&self, query: &PlayersQuery) -> Result<Page<Player>, PlayersError> {
+        self.players_db
+            .list_players(query.after, query.limit())
+            .await
     }
-    impl IdTokenVerifier for MockIdTokenVerifier {
-        fn verify<T: DeserializeOwned + 'static>(
-            &self,
-            id_token: &str,
-        ) -> impl Future<Output = Result<T, IdTokenVerifierError>> + Send {
-            self.verify(id_token)
+
+    async fn touch_last_seen(&self, player: &Player) -> Result<(), PlayersError> {
+        let seen_recently = player
+            .last_seen_at
+            .is_some_and(|last_seen_at| Utc::now() - last_seen_at < LAST_SEEN_THROTTLE);
+        if seen_recently {
+            return Ok(());
         }
+
+        self.players_db.touch_last_seen(&player.id).await
     }
 
-    #[test]
-    fn third_party_id_token_claims_json_snapshot() {
-        insta::assert_json_snapshot!(&ThirdPartyIdTokenClaims::test());
+    async fn ban_player(
+        &self,
+        player_id: &PlayerId,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<(), PlayersError> {
+        self.players_db.set_banned_until(player_id, until).await
     }
 
-    #[tokio::test]
-    async fn players_service_sign_in_fails_if_google_id_token_verification_fails() {
-        let mut google_id_token_verifier = MockIdTokenVerifier::new();
-        google_id_token_verifier
-            .expect_verify::<ThirdPartyIdTokenClaims>()
-            .withf(|id_token| id_token == IdToken::test().as_str())
-            .returning(|_| {
-                Box::pin(async {
-                    Err(IdTokenVerifierError::Validation(
-                        ValidationError::MissingKeyId,
-                    ))
-                })
-            });
+    async fn request_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        if player_id == other_id {
+            Err(PlayersError::CannotBefriendSelf)?
+        }
 
-        let service = PlayersServiceDefault {
-            google_id_token_verifier,
-            players_db: MockPlayersDb::new(),
-            jwt_service: MockJwtService::new(),
-        };
+        match self.players_db.find_friendship(player_id, other_id).await? {
+            None => {
+                match self.players_db.find_player_by_id(other_id).await {
+                    Err(PlayersError::PlayerNotFound) => Err(PlayersError::ProfileNotFound)?,
+                    result => result?,
+                };
 
-        let result = service.sign_in(&SignInRequest::test_google()).await;
+                self.players_db
+                    .create_friendship(&Friendship {
+                        requester_id: *player_id,
+                        addressee_id: *other_id,
+                        state: FriendshipState::Pending,
+                    })
+                    .await?;
 
-        assert!(matches!(
-            result,
-            Err(PlayersError::IdToken(IdTokenVerifierError::Validation(
-                ValidationError::MissingKeyId,
-            )))
-        ));
+                self.notify_quietly(
+                    other_id,
+                    NotificationKind::FriendRequested,
+                    "You have a new friend request",
+                )
+                .await;
+                Ok(())
+            }
+            Some(Friendship {
+                state: FriendshipState::Pending,
+                requester_id,
+                ..
+            }) if requester_id == *other_id => {
+                self.players_db
+                    .set_friendship_state(other_id, player_id, FriendshipState::Accepted)
+                    .await?;
+
+                self.notify_quietly(
+                    other_id,
+                    NotificationKind::FriendRequestAccepted,
+                    "Your friend request has been accepted",
+                )
+                .await;
+                Ok(())
+            }
+            Some(Friendship {
+                state: FriendshipState::Blocked,
+                ..
+            }) => Err(PlayersError::FriendshipBlocked),
+            Some(_) => Ok(()),
+        }
     }
 
-    #[tokio::test]
-    async fn players_service_sign_in_fails_if_db_player_lookup_fails() {
-        let mut google_id_token_verifier = MockIdTokenVerifier::new();
-        google_id_token_verifier
-            .expect_verify::<ThirdPartyIdTokenClaims>()
-            .withf(|id_token| id_token == IdToken::test().as_str())
-            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+    async fn accept_friendship(
+        &self,
+        player_id: &PlayerId,
+        requester_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        match self
+            .players_db
+            .find_friendship(player_id, requester_id)
+            .await?
+        {
+            Some(Friendship {
+                state: FriendshipState::Pending,
+                requester_id: id,
+                ..
+            }) if id == *requester_id => {
+                self.players_db
+                    .set_friendship_state(requester_id, player_id, FriendshipState::Accepted)
+                    .await?;
 
-        let mut players_db = MockPlayersDb::new();
-        players_db
-            .expect_find_player_with_sign_in_method()
-            .with(eq(SignInMethod::test_google()))
-            .returning(|_| {
-                Box::pin(async { Err(PlayersError::Internal(anyhow::anyhow!("oops"))) })
-            });
+                self.notify_quietly(
+                    requester_id,
+                    NotificationKind::FriendRequestAccepted,
+                    "Your friend request has been accepted",
+                )
+                .await;
+                Ok(())
+            }
+            Some(Friendship {
+                state: FriendshipState::Accepted,
+                ..
+            }) => Ok(()),
+            _ => Err(PlayersError::FriendRequestNotFound),
+        }
+    }
 
-        let service = PlayersServiceDefault {
-            players_db,
-            google_id_token_verifier,
-            jwt_service: MockJwtService::new(),
+    async fn remove_friendship(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        self.players_db.delete_friendship(player_id, other_id).await
+    }
+
+    async fn block_player(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        if player_id == other_id {
+            Err(PlayersError::CannotBlockSelf)?
+        }
+        match self.players_db.find_player_by_id(other_id).await {
+            Err(PlayersError::PlayerNotFound) => Err(PlayersError::ProfileNotFound)?,
+            result => result?,
         };
 
-        let result = service.sign_in(&SignInRequest::test_google()).await;
+        self.players_db.block_player(player_id, other_id).await
+    }
 
-        assert!(matches!(result, Err(PlayersError::Internal(e)) if e.to_string() == "oops"));
+    async fn unblock_player(
+        &self,
+        player_id: &PlayerId,
+        other_id: &PlayerId,
+    ) -> Result<(), PlayersError> {
+        self.players_db.unblock_player(player_id, other_id).await
     }
 
-    #[tokio::test]
-    async fn players_service_sign_in_creates_player_if_player_does_not_exist() {
-        let mut google_id_token_verifier = MockIdTokenVerifier::new();
-        google_id_token_verifier
-            .expect_verify::<ThirdPartyIdTokenClaims>()
-            .withf(|id_token| id_token == IdToken::test().as_str())
-            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+    async fn friends(&self, player_id: &PlayerId) -> Result<Vec<Friend>, PlayersError> {
+        self.players_db.find_friends(player_id).await
+    }
 
-        let mut players_db = MockPlayersDb::new();
-        players_db
-            .expect_find_player_with_sign_in_method()
-            .with(eq(SignInMethod::test_google()))
-            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
-        players_db
-            .expect_create_player_with_sign_in_method()
-            .with(
-                mockall::predicate::always(),
-                eq(SignInMethod::test_google()),
-            )
-            .returning(|_, _| Box::pin(async { Ok(()) }));
+    async fn report_player(
+        &self,
+        reporter_id: &PlayerId,
+        request: &ReportRequest,
+    ) -> Result<Report, PlayersError> {
+        if !Report::is_valid_reason(&request.reason) {
+            Err(PlayersError::InvalidReportReason)?
+        }
+        match self
+            .players_db
+            .find_player_by_id(&request.reported_id)
+            .await
+        {
+            Err(PlayersError::PlayerNotFound) => Err(PlayersError::ProfileNotFound)?,
+            result => result?,
+        };
 
-        let mut jwt_service = MockJwtService::new();
-        jwt_service
-            .expect_create_token()
-            .returning(|_| Ok(AuthToken::test()));
+        let report = Report::new(
+            *reporter_id,
+            request.reported_id,
+            request.game_id,
+            request.reason.clone(),
+        );
+        self.players_db.create_report(&report).await?;
 
-        let service = PlayersServiceDefault {
-            players_db,
-            google_id_token_verifier,
-            jwt_service,
-        };
+        Ok(report)
+    }
 
-        let auth_token = service
-            .sign_in(&SignInRequest::test_google())
+    async fn open_reports(&self, query: &ReportsQuery) -> Result<Page<Report>, PlayersError> {
+        self.players_db
+            .list_open_reports(query.after, query.limit())
             .await
-            .unwrap();
+    }
 
-        assert_eq!(auth_token, AuthToken::test());
+    async fn resolve_report(&self, report_id: &ReportId) -> Result<(), PlayersError> {
+        self.players_db.resolve_report(report_id).await
+    }
+
+    async fn preferences(&self, player_id: &PlayerId) -> Result<PlayerPreferences, PlayersError> {
+        self.players_db.get_preferences(player_id).await
+    }
+
+    async fn set_preferences(
+        &self,
+        player_id: &PlayerId,
+        preferences: &PlayerPreferences,
+    ) -> Result<(), PlayersError> {
+        self.players_db
+            .set_preferences(player_id, preferences)
+            .await
+    }
+
+    async fn delete_player(&self, player_id: &PlayerId) -> Result<(), PlayersError> {
+        self.players_db.delete_player(player_id).await
+    }
+
+    async fn screen_name_availability(
+        &self,
+        screen_name: &str,
+    ) -> Result<ScreenNameAvailability, PlayersError> {
+        let screen_name = match PlayerScreenName::from_str(screen_name) {
+            Ok(screen_name) => screen_name,
+            Err(e) => return Ok(ScreenNameAvailability::unavailable(e.to_string())),
+        };
+
+        if self.players_db.screen_name_exists(&screen_name).await? {
+            Ok(ScreenNameAvailability::unavailable(
+                PlayersError::ScreenNameTaken.to_string(),
+            ))
+        } else {
+            Ok(ScreenNameAvailability::available())
+        }
+    }
+
+    async fn notify(
+        &self,
+        player_id: &PlayerId,
+        kind: NotificationKind,
+        message: &str,
+    ) -> Result<Notification, PlayersError> {
+        let notification = Notification::new(kind, message.into());
+
+        self.players_db
+            .create_notification(player_id, &notification)
+            .await?;
+
+        Ok(notification)
+    }
+
+    async fn notifications(
+        &self,
+        player_id: &PlayerId,
+        query: &NotificationsQuery,
+    ) -> Result<NotificationsPage, PlayersError> {
+        let limit = query
+            .limit
+            .unwrap_or(NotificationsPage::DEFAULT_LIMIT)
+            .clamp(1, NotificationsPage::MAX_LIMIT);
+
+        let notifications = self
+            .players_db
+            .find_notifications(player_id, query.unread_only, query.before, limit + 1)
+            .await?;
+
+        Ok(NotificationsPage::new(notifications, limit))
+    }
+
+    async fn mark_notifications_read(
+        &self,
+        player_id: &PlayerId,
+        ids: &[NotificationId],
+    ) -> Result<(), PlayersError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        self.players_db
+            .mark_notifications_read(player_id, ids)
+            .await
+    }
+}
+
+impl<D, GV, JS> PlayersServiceDefault<D, GV, JS>
+where
+    D: PlayersDb + Sync,
+    GV: IdTokenVerifier + Sync,
+    JS: JwtService + Sync,
+{
+    /// Creates a [TokenPair] for the given [Player] and stores its [RefreshToken] within the
+    /// given [RefreshTokenFamilyId].
+    async fn create_token_pair(
+        &self,
+        player: &Player,
+        family_id: RefreshTokenFamilyId,
+    ) -> Result<TokenPair, PlayersError> {
+        let token_pair = self
+            .jwt_service
+            .create_token_pair(player.id, player.role, family_id)?;
+
+        self.players_db
+            .create_refresh_token(&StoredRefreshToken {
+                hash: RefreshTokenHash::new(&token_pair.refresh_token),
+                family_id,
+                player_id: player.id,
+                expires_at: token_pair.refresh_token_expires_at,
+                rotated: false,
+                revoked: false,
+            })
+            .await?;
+
+        Ok(token_pair)
+    }
+
+    /// Finds a [Player] with the given [SignInMethod] or creates a new one if there is none.
+    /// Returns the [Player] along with whether it has been created.
+    async fn find_or_create_player(
+        &self,
+        sign_in_method: &SignInMethod,
+    ) -> Result<(Player, bool), PlayersError> {
+        match self
+            .players_db
+            .find_player_with_sign_in_method(sign_in_method)
+            .await
+        {
+            Ok(player) => Ok((player, false)),
+            Err(PlayersError::PlayerNotFound) => {
+                let player = self
+                    .players_db
+                    .create_player_with_sign_in_method(&Player::random(), sign_in_method)
+                    .await?;
+
+                Ok((player, true))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Creates a new guest [Player] signed in from the device with the given [DeviceId]. Guests
+    /// can't sign in again as [DeviceId]s are not secret, they keep their session with refresh
+    /// tokens until they link a [ThirdPartySignInMethod].
+    async fn create_guest(&self, device_id: &DeviceId) -> Result<Player, PlayersError> {
+        if !device_id.is_valid() {
+            Err(PlayersError::InvalidDeviceId)?
+        }
+
+        let sign_in_method = SignInMethod::Anonymous {
+            device_id: device_id.clone(),
+        };
+
+        self.players_db
+            .create_player_with_sign_in_method(&Player::random(), &sign_in_method)
+            .await
+    }
+
+    /// Enqueues a [Notification] like [PlayersService::notify], only logging failures as the event
+    /// the [Player] is notified about has happened already.
+    async fn notify_quietly(&self, player_id: &PlayerId, kind: NotificationKind, message: &str) {
+        if let Err(e) = self.notify(player_id, kind, message).await {
+            warn!("Failed to notify player {player_id:?} about {kind:?}: {e}");
+        }
+    }
+
+    /// Registers a new [Player] with the given email and [Password].
+    async fn register(&self, email: &str, password: &Password) -> Result<Player, PlayersError> {
+        let email = Email::from_str(email)?;
+        password.validate()?;
+
+        let password = password.clone();
+        let password_hash = tokio::task::spawn_blocking(move || PasswordHash::new(&password))
+            .await
+            .context("join password hashing")??;
+
+        let player = Player::random();
+        let sign_in_method = SignInMethod::EmailPassword(EmailPasswordSignInMethod {
+            email,
+            password_hash,
+        });
+
+        self.players_db
+            .create_player_with_sign_in_method(&player, &sign_in_method)
+            .await
+    }
+
+    /// Finds a [Player] registered with the given email and [Password].
+    ///
+    /// Fails with [PlayersError::InvalidCredentials] regardless of whether the email is unknown or
+    /// the password is wrong. Unknown emails are checked against [PasswordHash::dummy] so that
+    /// they take as long as known ones.
+    async fn email_login(&self, email: &str, password: &Password) -> Result<Player, PlayersError> {
+        let found = match Email::from_str(email) {
+            Ok(email) => match self.players_db.find_player_with_email(&email).await {
+                Ok(found) => Some(found),
+                Err(PlayersError::PlayerNotFound) => None,
+                Err(e) => Err(e)?,
+            },
+            Err(_) => None,
+        };
+
+        let password = password.clone();
+        let (player, password_hash) = match found {
+            Some((player, password_hash)) => (Some(player), password_hash),
+            None => (None, PasswordHash::dummy().clone()),
+        };
+        let verified = tokio::task::spawn_blocking(move || password_hash.verify(&password))
+            .await
+            .context("join password verification")?;
+
+        match player {
+            Some(player) if verified => Ok(player),
+            _ => Err(PlayersError::InvalidCredentials),
+        }
+    }
+
+    /// Verifies the given [IdToken] issued by the given [ThirdPartySignInProvider] and returns
+    /// the [ThirdPartySignInMethod] it identifies. If the [ClientPlatform] is given, fails with
+    /// [PlayersError::IdTokenWrongPlatform] if the [IdToken] is issued for a known client of
+    /// another platform. Marks the [IdToken] as used if replay protection is enabled, failing
+    /// with [PlayersError::IdTokenReused] if it has already been used.
+    async fn verify_id_token(
+        &self,
+        provider: ThirdPartySignInProvider,
+        id_token: &IdToken,
+        platform: Option<ClientPlatform>,
+    ) -> Result<ThirdPartySignInMethod, PlayersError> {
+        let id_token_verifier = self
+            .id_token_verifiers
+            .get(&provider)
+            .ok_or(PlayersError::SignInProviderNotConfigured(provider))?;
+
+        let permit = self.id_token_verification_limit.acquire().await?;
+        let claims = id_token_verifier
+            .verify::<ThirdPartyIdTokenClaims>(id_token.as_ref())
+            .await?;
+        drop(permit);
+
+        if let Some(platform) = platform
+            && let Some(aud_platform) = self.platform_of(&claims.aud)
+            && aud_platform != platform
+        {
+            Err(PlayersError::IdTokenWrongPlatform(aud_platform))?
+        }
+
+        if let Some(ref guard) = self.id_token_replay_guard {
+            guard.use_token(id_token, claims.exp)?;
+        }
+
+        Ok(ThirdPartySignInMethod {
+            provider,
+            user_id: claims.sub,
+        })
+    }
+
+    /// Signs in with the [ThirdPartySignInMethod] identified by the given [IdToken], see
+    /// [PlayersServiceDefault::verify_id_token]. Forgets the [IdToken] as used if the sign-in fails
+    /// after its verification, so that the client can retry with it.
+    async fn third_party_sign_in(
+        &self,
+        provider: ThirdPartySignInProvider,
+        id_token: &IdToken,
+        platform: Option<ClientPlatform>,
+    ) -> Result<SignedIn, PlayersError> {
+        let sign_in_method =
+            SignInMethod::ThirdParty(self.verify_id_token(provider, id_token, platform).await?);
+
+        let signed_in = async {
+            let (player, is_new_player) = self.find_or_create_player(&sign_in_method).await?;
+            self.complete_sign_in(player, is_new_player).await
+        }
+        .await;
+
+        signed_in.inspect_err(|_| self.forget_id_token(id_token))
+    }
+
+    /// Completes the sign-in of the given [Player] by creating a [TokenPair] of a new refresh token
+    /// family. Fails with [PlayersError::PlayerBanned] if the [Player] is banned.
+    async fn complete_sign_in(
+        &self,
+        player: Player,
+        is_new_player: bool,
+    ) -> Result<SignedIn, PlayersError> {
+        if let Some(until) = player.active_ban() {
+            Err(PlayersError::PlayerBanned(until))?
+        }
+
+        let token_pair = self
+            .create_token_pair(&player, RefreshTokenFamilyId::random())
+            .await?;
+
+        Ok(SignedIn {
+            token_pair,
+            player,
+            is_new_player,
+        })
+    }
+
+    /// Forgets the given [IdToken] as used by [PlayersServiceDefault::verify_id_token], if replay
+    /// protection is enabled.
+    fn forget_id_token(&self, id_token: &IdToken) {
+        if let Some(ref guard) = self.id_token_replay_guard {
+            guard.forget_token(id_token);
+        }
+    }
+
+    /// Returns the [ClientPlatform] of a known client among the given [Aud]s, if any.
+    fn platform_of(&self, aud: &OneOrVec<Aud>) -> Option<ClientPlatform> {
+        let auds = match aud {
+            OneOrVec::One(aud) => std::slice::from_ref(aud),
+            OneOrVec::Vec(auds) => auds.as_slice(),
+        };
+        let audiences = &self.id_token_platform_audiences;
+
+        auds.iter().find_map(|aud| {
+            [
+                (ClientPlatform::Web, &audiences.web),
+                (ClientPlatform::Android, &audiences.android),
+                (ClientPlatform::Ios, &audiences.ios),
+            ]
+            .into_iter()
+            .find(|(_, platform_audiences)| platform_audiences.contains(aud))
+            .map(|(platform, _)| platform)
+        })
+    }
+}
+
+/// Target ID token claims. Used with [IdTokenVerifier::verify] when signing in with third party
+/// sign in providers.
+#[derive(Deserialize, Serialize)]
+struct ThirdPartyIdTokenClaims {
+    /// Subject [ThirdPartySignInUserId] the ID token is issued for.
+    sub: ThirdPartySignInUserId,
+
+    /// When the ID token expires.
+    exp: i64,
+
+    /// Audience the ID token is issued for.
+    aud: OneOrVec<Aud>,
+}
+
+impl ThirdPartyIdTokenClaims {
+    #[cfg(test)]
+    /// Returns a test [ThirdPartyIdTokenClaims].
+    fn test() -> ThirdPartyIdTokenClaims {
+        ThirdPartyIdTokenClaims {
+            sub: ThirdPartySignInUserId::test(),
+            exp: 4102444800,
+            aud: OneOrVec::One(Aud::new("test-aud")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use id_token_verifier::validation::ValidationError;
+    use mockall::mock;
+    use mockall::predicate::{always, eq};
+    use serde::de::DeserializeOwned;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    mock! {
+        IdTokenVerifier {
+            fn verify<T: DeserializeOwned + 'static>(
+                &self,
+                id_token: &str
+            ) -> impl Future<Output = Result<T, IdTokenVerifierError>> + Send;
+        }
+    }
+    impl IdTokenVerifier for MockIdTokenVerifier {
+        fn verify<T: DeserializeOwned + 'static>(
+            &self,
+            id_token: &str,
+        ) -> impl Future<Output = Result<T, IdTokenVerifierError>> + Send {
+            self.verify(id_token)
+        }
+    }
+
+    #[test]
+    fn third_party_id_token_claims_json_snapshot() {
+        insta::assert_json_snapshot!(&ThirdPartyIdTokenClaims::test());
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_fails_if_google_id_token_verification_fails() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .withf(|id_token| id_token == IdToken::test().as_str())
+            .returning(|_| {
+                Box::pin(async {
+                    Err(IdTokenVerifierError::Validation(
+                        ValidationError::MissingKeyId,
+                    ))
+                })
+            });
+
+        let service = PlayersServiceDefault {
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            players_db: MockPlayersDb::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.sign_in(&SignInRequest::test_google()).await;
+
+        assert!(matches!(
+            result,
+            Err(PlayersError::IdToken(IdTokenVerifierError::Validation(
+                ValidationError::MissingKeyId,
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_fails_if_db_player_lookup_fails() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .withf(|id_token| id_token == IdToken::test().as_str())
+            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_google()))
+            .returning(|_| {
+                Box::pin(async { Err(PlayersError::Internal(anyhow::anyhow!("oops"))) })
+            });
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.sign_in(&SignInRequest::test_google()).await;
+
+        assert!(matches!(result, Err(PlayersError::Internal(e)) if e.to_string() == "oops"));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_creates_player_if_player_does_not_exist() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .withf(|id_token| id_token == IdToken::test().as_str())
+            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_google()))
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        players_db
+            .expect_create_player_with_sign_in_method()
+            .with(
+                mockall::predicate::always(),
+                eq(SignInMethod::test_google()),
+            )
+            .returning(|player, _| Box::pin(std::future::ready(Ok(player.clone()))));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let signed_in = service
+            .sign_in(&SignInRequest::test_google())
+            .await
+            .unwrap();
+
+        assert_eq!(signed_in.token_pair, TokenPair::test());
+        assert!(signed_in.is_new_player);
     }
 
     #[tokio::test]
@@ -271,22 +1116,32 @@ mod tests {
 
         let mut jwt_service = MockJwtService::new();
         jwt_service
-            .expect_create_token()
-            .with(eq(PlayerId::test()))
-            .returning(|_| Ok(AuthToken::test()));
+            .expect_create_token_pair()
+            .with(eq(PlayerId::test()), eq(PlayerRole::Player), always())
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
 
         let service = PlayersServiceDefault {
             players_db,
-            google_id_token_verifier,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
             jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
         };
 
-        let auth_token = service
+        let signed_in = service
             .sign_in(&SignInRequest::test_google())
             .await
             .unwrap();
 
-        assert_eq!(auth_token, AuthToken::test());
+        assert_eq!(signed_in.token_pair, TokenPair::test());
+        assert!(!signed_in.is_new_player);
     }
 
     #[tokio::test]
@@ -299,12 +1154,1527 @@ mod tests {
 
         let service = PlayersServiceDefault {
             players_db,
-            google_id_token_verifier: MockIdTokenVerifier::new(),
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
             jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
         };
 
         let player = service.player_by_id(&PlayerId::test()).await.unwrap();
 
         assert_eq!(player, Player::test());
     }
+
+    #[tokio::test]
+    async fn players_service_touch_last_seen_is_throttled() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_touch_last_seen()
+            .with(eq(PlayerId::test()))
+            .times(2)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let service = service_with_players_db(players_db);
+
+        for last_seen_at in [
+            None,
+            Some(Utc::now() - LAST_SEEN_THROTTLE),
+            Some(Utc::now() - LAST_SEEN_THROTTLE / 2),
+        ] {
+            let player = Player {
+                last_seen_at,
+                ..Player::test()
+            };
+
+            service.touch_last_seen(&player).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_players_by_ids_lookups_players_in_db() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_players_by_ids()
+            .withf(|player_ids| player_ids == [PlayerId::test()])
+            .returning(|_| Box::pin(async { Ok(vec![Player::test()]) }));
+        let service = service_with_players_db(players_db);
+
+        let players = service.players_by_ids(&[PlayerId::test()]).await.unwrap();
+
+        assert_eq!(players, vec![Player::test()]);
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_rejects_replayed_id_token() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .withf(|id_token| id_token == IdToken::test().as_str())
+            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_google()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service,
+            id_token_replay_guard: Some(IdTokenReplayGuard::new(IdTokenReplayProtectionConfig {
+                enabled: true,
+                max_entries: 10,
+            })),
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let first = service.sign_in(&SignInRequest::test_google()).await;
+        let replay = service.sign_in(&SignInRequest::test_google()).await;
+
+        assert!(first.is_ok());
+        assert!(matches!(replay, Err(PlayersError::IdTokenReused)));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_accepts_id_token_again_after_failed_sign_in() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .withf(|id_token| id_token == IdToken::test().as_str())
+            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_google()))
+            .times(1)
+            .returning(|_| {
+                Box::pin(async { Err(PlayersError::Internal(anyhow::anyhow!("db down"))) })
+            });
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_google()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service,
+            id_token_replay_guard: Some(IdTokenReplayGuard::new(IdTokenReplayProtectionConfig {
+                enabled: true,
+                max_entries: 10,
+            })),
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let failed = service.sign_in(&SignInRequest::test_google()).await;
+        let retry = service.sign_in(&SignInRequest::test_google()).await;
+        let replay = service.sign_in(&SignInRequest::test_google()).await;
+
+        assert!(matches!(failed, Err(PlayersError::Internal(_))));
+        assert!(retry.is_ok());
+        assert!(matches!(replay, Err(PlayersError::IdTokenReused)));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_respects_id_token_verification_limit() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .returning({
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                move |_| {
+                    let in_flight = in_flight.clone();
+                    let max_in_flight = max_in_flight.clone();
+                    Box::pin(async move {
+                        let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(current, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        Ok(ThirdPartyIdTokenClaims::test())
+                    })
+                }
+            });
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::new(
+                IdTokenVerificationLimitConfig {
+                    max_concurrent: 2,
+                    acquire_timeout: Duration::from_secs(5),
+                },
+            ),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let request = SignInRequest::test_google();
+        let results = tokio::join!(
+            service.sign_in(&request),
+            service.sign_in(&request),
+            service.sign_in(&request),
+            service.sign_in(&request),
+        );
+
+        assert!(results.0.is_ok() && results.1.is_ok() && results.2.is_ok() && results.3.is_ok());
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_sheds_when_id_token_verification_limit_is_exhausted() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    Ok(ThirdPartyIdTokenClaims::test())
+                })
+            });
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::new(
+                IdTokenVerificationLimitConfig {
+                    max_concurrent: 1,
+                    acquire_timeout: Duration::from_millis(10),
+                },
+            ),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let request = SignInRequest::test_google();
+        let (first, second) = tokio::join!(service.sign_in(&request), service.sign_in(&request));
+
+        assert!(first.is_ok());
+        assert!(matches!(
+            second,
+            Err(PlayersError::IdTokenVerificationOverloaded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_creates_player_with_apple_sign_in_method() {
+        let mut apple_id_token_verifier = MockIdTokenVerifier::new();
+        apple_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .withf(|id_token| id_token == IdToken::test().as_str())
+            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_apple()))
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        players_db
+            .expect_create_player_with_sign_in_method()
+            .with(mockall::predicate::always(), eq(SignInMethod::test_apple()))
+            .times(1)
+            .returning(|player, _| Box::pin(std::future::ready(Ok(player.clone()))));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([
+                (ThirdPartySignInProvider::Google, MockIdTokenVerifier::new()),
+                (ThirdPartySignInProvider::Apple, apple_id_token_verifier),
+            ]),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let signed_in = service.sign_in(&SignInRequest::test_apple()).await.unwrap();
+
+        assert_eq!(signed_in.token_pair, TokenPair::test());
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_fails_if_provider_is_not_configured() {
+        let service = PlayersServiceDefault {
+            players_db: MockPlayersDb::new(),
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                MockIdTokenVerifier::new(),
+            )]),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.sign_in(&SignInRequest::test_apple()).await;
+
+        assert!(matches!(
+            result,
+            Err(PlayersError::SignInProviderNotConfigured(
+                ThirdPartySignInProvider::Apple
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_registers_player_with_email_and_password() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_create_player_with_sign_in_method()
+            .withf(|_, sign_in_method| {
+                matches!(
+                    sign_in_method,
+                    SignInMethod::EmailPassword(email_password)
+                        if email_password.email == Email::test()
+                            && email_password.password_hash.verify(&Password::test())
+                )
+            })
+            .times(1)
+            .returning(|player, _| Box::pin(std::future::ready(Ok(player.clone()))));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let signed_in = service
+            .sign_in(&SignInRequest::test_register())
+            .await
+            .unwrap();
+
+        assert_eq!(signed_in.token_pair, TokenPair::test());
+        assert!(signed_in.is_new_player);
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_rejects_registration_with_taken_email() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_create_player_with_sign_in_method()
+            .returning(|_, _| Box::pin(async { Err(PlayersError::EmailAlreadyRegistered) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.sign_in(&SignInRequest::test_register()).await;
+
+        assert!(matches!(result, Err(PlayersError::EmailAlreadyRegistered)));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_rejects_registration_with_short_password() {
+        let service = PlayersServiceDefault {
+            players_db: MockPlayersDb::new(),
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service
+            .sign_in(&SignInRequest::Register {
+                email: "player@example.com".into(),
+                password: Password("short".into()),
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PlayersError::InvalidPassword(InvalidPassword::TooShort))
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_logs_in_with_email_and_password() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_email()
+            .with(eq(Email::test()))
+            .returning(|_| {
+                Box::pin(async {
+                    Ok((
+                        Player::test(),
+                        PasswordHash::new(&Password::test()).unwrap(),
+                    ))
+                })
+            });
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .with(eq(PlayerId::test()), eq(PlayerRole::Player), always())
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let signed_in = service
+            .sign_in(&SignInRequest::test_email_login())
+            .await
+            .unwrap();
+
+        assert_eq!(signed_in.token_pair, TokenPair::test());
+        assert!(!signed_in.is_new_player);
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_rejects_wrong_password_and_unknown_email() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_email()
+            .with(eq(Email::test()))
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    let password_hash = PasswordHash::new(&Password::test()).unwrap();
+                    Ok((Player::test(), password_hash))
+                })
+            });
+        players_db
+            .expect_find_player_with_email()
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let wrong_password = service
+            .sign_in(&SignInRequest::EmailLogin {
+                email: "player@example.com".into(),
+                password: Password("wrong-password".into()),
+            })
+            .await;
+        let unknown_email = service
+            .sign_in(&SignInRequest::EmailLogin {
+                email: "unknown@example.com".into(),
+                password: Password::test(),
+            })
+            .await;
+
+        assert!(matches!(
+            wrong_password,
+            Err(PlayersError::InvalidCredentials)
+        ));
+        assert!(matches!(
+            unknown_email,
+            Err(PlayersError::InvalidCredentials)
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_creates_new_guest_player_every_time() {
+        let mut players_db = MockPlayersDb::new();
+        players_db.expect_find_player_with_sign_in_method().never();
+        players_db
+            .expect_create_player_with_sign_in_method()
+            .with(
+                mockall::predicate::always(),
+                eq(SignInMethod::test_anonymous()),
+            )
+            .times(2)
+            .returning(|player, _| Box::pin(std::future::ready(Ok(player.clone()))));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let first = service.sign_in(&SignInRequest::test_guest()).await.unwrap();
+        let second = service.sign_in(&SignInRequest::test_guest()).await.unwrap();
+
+        assert_eq!(first.token_pair, TokenPair::test());
+        assert!(first.is_new_player);
+        assert!(second.is_new_player);
+        assert_ne!(first.player.id, second.player.id);
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_fails_if_device_id_is_invalid() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_create_player_with_sign_in_method()
+            .never();
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        for device_id in [String::new(), "d".repeat(DeviceId::MAX_SIZE + 1)] {
+            let result = service
+                .sign_in(&SignInRequest::Guest {
+                    device_id: DeviceId(device_id),
+                })
+                .await;
+
+            assert!(matches!(result, Err(PlayersError::InvalidDeviceId)));
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_fails_if_player_is_banned() {
+        let until = Utc::now() + chrono::TimeDelta::hours(1);
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_email()
+            .returning(move |_| {
+                Box::pin(std::future::ready(Ok((
+                    Player {
+                        banned_until: Some(until),
+                        ..Player::test()
+                    },
+                    PasswordHash::new(&Password::test()).unwrap(),
+                ))))
+            });
+        players_db.expect_create_refresh_token().never();
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service.expect_create_token_pair().never();
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.sign_in(&SignInRequest::test_email_login()).await;
+
+        assert!(
+            matches!(result, Err(PlayersError::PlayerBanned(banned_until)) if banned_until == until)
+        );
+    }
+
+    #[tokio::test]
+    async fn players_service_link_sign_in_method_upgrades_guest_to_google() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .withf(|id_token| id_token == IdToken::test().as_str())
+            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_google()))
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        players_db
+            .expect_add_sign_in_method()
+            .with(eq(PlayerId::test()), eq(SignInMethod::test_google()))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service
+            .link_sign_in_method(&PlayerId::test(), &LinkSignInMethodRequest::test_google())
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn players_service_link_sign_in_method_fails_if_linked_to_another_player() {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .returning(|_| Box::pin(async { Ok(ThirdPartyIdTokenClaims::test()) }));
+
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .with(eq(SignInMethod::test_google()))
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_db.expect_add_sign_in_method().never();
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service
+            .link_sign_in_method(&PlayerId::random(), &LinkSignInMethodRequest::test_google())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PlayersError::SignInMethodAlreadyLinked)
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_refresh_fails_if_player_is_banned() {
+        let until = Utc::now() + chrono::TimeDelta::hours(1);
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_refresh_token()
+            .returning(|_| Box::pin(async { Ok(StoredRefreshToken::test()) }));
+        players_db
+            .expect_rotate_refresh_token()
+            .returning(|_| Box::pin(async { Ok(true) }));
+        players_db.expect_find_player_by_id().returning(move |_| {
+            Box::pin(std::future::ready(Ok(Player {
+                banned_until: Some(until),
+                ..Player::test()
+            })))
+        });
+        players_db.expect_create_refresh_token().never();
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service.expect_create_token_pair().never();
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.refresh(&RefreshToken::test()).await;
+
+        assert!(
+            matches!(result, Err(PlayersError::PlayerBanned(banned_until)) if banned_until == until)
+        );
+    }
+
+    #[tokio::test]
+    async fn players_service_refresh_rotates_refresh_token() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_refresh_token()
+            .with(eq(RefreshTokenHash::new(&RefreshToken::test())))
+            .returning(|_| Box::pin(async { Ok(StoredRefreshToken::test()) }));
+        players_db
+            .expect_rotate_refresh_token()
+            .with(eq(RefreshTokenHash::new(&RefreshToken::test())))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(true) }));
+        players_db
+            .expect_create_refresh_token()
+            .withf(|refresh_token| {
+                refresh_token.family_id == RefreshTokenFamilyId::test()
+                    && refresh_token.player_id == PlayerId::test()
+                    && !refresh_token.rotated
+            })
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_db.expect_revoke_refresh_token_family().never();
+        players_db
+            .expect_find_player_by_id()
+            .with(eq(PlayerId::test()))
+            .returning(|_| Box::pin(async { Ok(Player::test_admin()) }));
+
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .with(
+                eq(PlayerId::test()),
+                eq(PlayerRole::Admin),
+                eq(RefreshTokenFamilyId::test()),
+            )
+            .returning(|_, _, _| Ok(TokenPair::test()));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let token_pair = service.refresh(&RefreshToken::test()).await.unwrap();
+
+        assert_eq!(token_pair, TokenPair::test());
+    }
+
+    #[tokio::test]
+    async fn players_service_refresh_revokes_chain_on_reuse() {
+        let mut players_db = MockPlayersDb::new();
+        players_db.expect_find_refresh_token().returning(|_| {
+            Box::pin(async {
+                Ok(StoredRefreshToken {
+                    rotated: true,
+                    ..StoredRefreshToken::test()
+                })
+            })
+        });
+        players_db.expect_rotate_refresh_token().never();
+        players_db
+            .expect_revoke_refresh_token_family()
+            .with(eq(RefreshTokenFamilyId::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.refresh(&RefreshToken::test()).await;
+
+        assert!(matches!(result, Err(PlayersError::RefreshTokenReused)));
+    }
+
+    #[tokio::test]
+    async fn players_service_refresh_revokes_chain_if_rotated_concurrently() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_refresh_token()
+            .returning(|_| Box::pin(async { Ok(StoredRefreshToken::test()) }));
+        players_db
+            .expect_rotate_refresh_token()
+            .returning(|_| Box::pin(async { Ok(false) }));
+        players_db
+            .expect_revoke_refresh_token_family()
+            .with(eq(RefreshTokenFamilyId::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let result = service.refresh(&RefreshToken::test()).await;
+
+        assert!(matches!(result, Err(PlayersError::RefreshTokenReused)));
+    }
+
+    #[tokio::test]
+    async fn players_service_refresh_rejects_expired_and_revoked_refresh_tokens() {
+        for stored in [
+            StoredRefreshToken {
+                expires_at: Utc::now() - chrono::Duration::seconds(1),
+                ..StoredRefreshToken::test()
+            },
+            StoredRefreshToken {
+                revoked: true,
+                ..StoredRefreshToken::test()
+            },
+        ] {
+            let mut players_db = MockPlayersDb::new();
+            players_db
+                .expect_find_refresh_token()
+                .returning(move |_| Box::pin(std::future::ready(Ok(stored.clone()))));
+            players_db.expect_rotate_refresh_token().never();
+
+            let service = PlayersServiceDefault {
+                players_db,
+                id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+                jwt_service: MockJwtService::new(),
+                id_token_replay_guard: None,
+                id_token_verification_limit: IdTokenVerificationLimit::test(),
+                id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+            };
+
+            let result = service.refresh(&RefreshToken::test()).await;
+
+            assert!(matches!(result, Err(PlayersError::RefreshTokenInvalid)));
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_refresh_fails_after_logout() -> anyhow::Result<()> {
+        let revoked_families = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut jwt_players_db = MockPlayersDb::new();
+        jwt_players_db
+            .expect_is_token_revoked()
+            .returning(|_| Box::pin(async { Ok(false) }));
+        jwt_players_db
+            .expect_revoke_token()
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        jwt_players_db
+            .expect_revoke_refresh_token_family()
+            .times(1)
+            .returning({
+                let revoked_families = revoked_families.clone();
+                move |family_id| {
+                    revoked_families.lock().unwrap().push(*family_id);
+                    Box::pin(async { Ok(()) })
+                }
+            });
+        let jwt_service = JwtServiceDefault::new(crate::config::JwtConfig::test(), jwt_players_db)?;
+        let token_pair = jwt_service.create_token_pair(
+            PlayerId::test(),
+            PlayerRole::Player,
+            RefreshTokenFamilyId::test(),
+        )?;
+        let mut players_db = MockPlayersDb::new();
+        players_db.expect_find_refresh_token().returning({
+            let revoked_families = revoked_families.clone();
+            move |_| {
+                let revoked = revoked_families
+                    .lock()
+                    .unwrap()
+                    .contains(&RefreshTokenFamilyId::test());
+                Box::pin(async move {
+                    Ok(StoredRefreshToken {
+                        revoked,
+                        ..StoredRefreshToken::test()
+                    })
+                })
+            }
+        });
+        players_db.expect_rotate_refresh_token().never();
+        let service = PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::<_, MockIdTokenVerifier>::new(),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        };
+
+        let claims = service
+            .jwt_service
+            .verify_token(&token_pair.auth_token)
+            .await?;
+        service.jwt_service.revoke_token(&claims).await?;
+        let result = service.refresh(&token_pair.refresh_token).await;
+
+        assert!(matches!(result, Err(PlayersError::RefreshTokenInvalid)));
+
+        Ok(())
+    }
+
+    fn service_with_players_db(
+        players_db: MockPlayersDb,
+    ) -> PlayersServiceDefault<MockPlayersDb, MockIdTokenVerifier, MockJwtService> {
+        PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::new(),
+            jwt_service: MockJwtService::new(),
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_report_player_creates_open_report() {
+        let request = ReportRequest::test();
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_by_id()
+            .with(eq(request.reported_id))
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_db
+            .expect_create_report()
+            .withf(|report| {
+                report.reporter_id == Some(PlayerId::test())
+                    && report.reported_id == ReportRequest::test().reported_id
+                    && report.status == ReportStatus::Open
+            })
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let service = service_with_players_db(players_db);
+
+        let report = service
+            .report_player(&PlayerId::test(), &request)
+            .await
+            .unwrap();
+
+        assert_eq!(report.reason, request.reason);
+    }
+
+    #[tokio::test]
+    async fn players_service_report_player_rejects_invalid_and_too_frequent_reports() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_db
+            .expect_create_report()
+            .times(1)
+            .returning(|_| Box::pin(async { Err(PlayersError::ReportRateLimited) }));
+        let service = service_with_players_db(players_db);
+
+        let blank = service
+            .report_player(
+                &PlayerId::test(),
+                &ReportRequest {
+                    reason: " ".into(),
+                    ..ReportRequest::test()
+                },
+            )
+            .await;
+        let rate_limited = service
+            .report_player(&PlayerId::test(), &ReportRequest::test())
+            .await;
+
+        assert!(matches!(blank, Err(PlayersError::InvalidReportReason)));
+        assert!(matches!(rate_limited, Err(PlayersError::ReportRateLimited)));
+    }
+
+    #[tokio::test]
+    async fn players_service_request_friendship_creates_pending_friendship() {
+        let friendship = Friendship::test();
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_friendship()
+            .with(eq(friendship.requester_id), eq(friendship.addressee_id))
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        players_db
+            .expect_find_player_by_id()
+            .with(eq(friendship.addressee_id))
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_db
+            .expect_create_friendship()
+            .with(eq(friendship.clone()))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_db
+            .expect_create_notification()
+            .withf(move |player_id, notification| {
+                player_id == &friendship.addressee_id
+                    && notification.kind == NotificationKind::FriendRequested
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let service = service_with_players_db(players_db);
+
+        service
+            .request_friendship(&friendship.requester_id, &friendship.addressee_id)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn players_service_request_friendship_succeeds_if_notification_fails() {
+        let friendship = Friendship::test();
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_friendship()
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        players_db
+            .expect_find_player_by_id()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_db
+            .expect_create_friendship()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        players_db.expect_create_notification().returning(|_, _| {
+            Box::pin(async { Err(PlayersError::Internal(anyhow::anyhow!("db is down"))) })
+        });
+        let service = service_with_players_db(players_db);
+
+        let result = service
+            .request_friendship(&friendship.requester_id, &friendship.addressee_id)
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn players_service_request_friendship_is_idempotent() {
+        let friendship = Friendship::test();
+        for state in [FriendshipState::Pending, FriendshipState::Accepted] {
+            let mut players_db = MockPlayersDb::new();
+            players_db.expect_find_friendship().returning(move |_, _| {
+                Box::pin(std::future::ready(Ok(Some(Friendship {
+                    state,
+                    ..Friendship::test()
+                }))))
+            });
+            players_db.expect_create_friendship().never();
+            players_db.expect_set_friendship_state().never();
+            let service = service_with_players_db(players_db);
+
+            service
+                .request_friendship(&friendship.requester_id, &friendship.addressee_id)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_request_friendship_accepts_request_of_other_player() {
+        let friendship = Friendship::test();
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_friendship()
+            .returning(|_, _| Box::pin(async { Ok(Some(Friendship::test())) }));
+        players_db
+            .expect_set_friendship_state()
+            .with(
+                eq(friendship.requester_id),
+                eq(friendship.addressee_id),
+                eq(FriendshipState::Accepted),
+            )
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+        players_db
+            .expect_create_notification()
+            .withf(move |player_id, notification| {
+                player_id == &friendship.requester_id
+                    && notification.kind == NotificationKind::FriendRequestAccepted
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let service = service_with_players_db(players_db);
+
+        service
+            .request_friendship(&friendship.addressee_id, &friendship.requester_id)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn players_service_request_friendship_rejects_self_and_blocked() {
+        let friendship = Friendship::test();
+        let mut players_db = MockPlayersDb::new();
+        players_db.expect_find_friendship().returning(|_, _| {
+            Box::pin(async {
+                Ok(Some(Friendship {
+                    state: FriendshipState::Blocked,
+                    ..Friendship::test()
+                }))
+            })
+        });
+        let service = service_with_players_db(players_db);
+
+        let self_friendship = service
+            .request_friendship(&friendship.requester_id, &friendship.requester_id)
+            .await;
+        let blocked = service
+            .request_friendship(&friendship.requester_id, &friendship.addressee_id)
+            .await;
+
+        assert!(matches!(
+            self_friendship,
+            Err(PlayersError::CannotBefriendSelf)
+        ));
+        assert!(matches!(blocked, Err(PlayersError::FriendshipBlocked)));
+    }
+
+    #[tokio::test]
+    async fn players_service_block_player_blocks_existing_players_only() {
+        let friendship = Friendship::test();
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_by_id()
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        players_db.expect_block_player().never();
+        let service = service_with_players_db(players_db);
+
+        let unknown = service
+            .block_player(&friendship.requester_id, &friendship.addressee_id)
+            .await;
+        let self_block = service
+            .block_player(&friendship.requester_id, &friendship.requester_id)
+            .await;
+
+        assert!(matches!(unknown, Err(PlayersError::ProfileNotFound)));
+        assert!(matches!(self_block, Err(PlayersError::CannotBlockSelf)));
+    }
+
+    #[tokio::test]
+    async fn players_service_accept_friendship_accepts_only_incoming_requests() {
+        let friendship = Friendship::test();
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_friendship()
+            .returning(|_, _| Box::pin(async { Ok(Some(Friendship::test())) }));
+        players_db
+            .expect_set_friendship_state()
+            .with(
+                eq(friendship.requester_id),
+                eq(friendship.addressee_id),
+                eq(FriendshipState::Accepted),
+            )
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+        players_db
+            .expect_create_notification()
+            .withf(move |player_id, notification| {
+                player_id == &friendship.requester_id
+                    && notification.kind == NotificationKind::FriendRequestAccepted
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let service = service_with_players_db(players_db);
+
+        let incoming = service
+            .accept_friendship(&friendship.addressee_id, &friendship.requester_id)
+            .await;
+        let outgoing = service
+            .accept_friendship(&friendship.requester_id, &friendship.addressee_id)
+            .await;
+
+        assert!(incoming.is_ok());
+        assert!(matches!(outgoing, Err(PlayersError::FriendRequestNotFound)));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_methods_lists_sign_in_methods_in_db() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_sign_in_methods()
+            .with(eq(PlayerId::test()))
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(vec![LinkedSignInMethod::ThirdParty {
+                        provider: ThirdPartySignInProvider::Google,
+                    }])
+                })
+            });
+        let service = service_with_players_db(players_db);
+
+        let sign_in_methods = service.sign_in_methods(&PlayerId::test()).await.unwrap();
+
+        assert_eq!(
+            sign_in_methods,
+            vec![LinkedSignInMethod::ThirdParty {
+                provider: ThirdPartySignInProvider::Google,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn players_service_delete_player_deletes_player_in_db() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_delete_player()
+            .with(eq(PlayerId::test()))
+            .times(1)
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        let service = service_with_players_db(players_db);
+
+        let result = service.delete_player(&PlayerId::test()).await;
+
+        assert!(matches!(result, Err(PlayersError::PlayerNotFound)));
+    }
+
+    #[tokio::test]
+    async fn players_service_screen_name_availability_checks_db_for_valid_names() {
+        for (exists, expected) in [
+            (false, ScreenNameAvailability::available()),
+            (
+                true,
+                ScreenNameAvailability::unavailable("screen name is already taken".into()),
+            ),
+        ] {
+            let mut players_db = MockPlayersDb::new();
+            players_db
+                .expect_screen_name_exists()
+                .with(eq(PlayerScreenName::test()))
+                .times(1)
+                .returning(move |_| Box::pin(std::future::ready(Ok(exists))));
+            let service = service_with_players_db(players_db);
+
+            let availability = service
+                .screen_name_availability(" test-screen-name ")
+                .await
+                .unwrap();
+
+            assert_eq!(availability, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_screen_name_availability_reports_invalid_names() {
+        let mut players_db = MockPlayersDb::new();
+        players_db.expect_screen_name_exists().never();
+        let service = service_with_players_db(players_db);
+
+        for (screen_name, reason) in [
+            ("  ", InvalidPlayerScreenName::Empty),
+            (
+                &"w".repeat(PlayerScreenName::MAX_SIZE + 1),
+                InvalidPlayerScreenName::ExceedsMaxSize,
+            ),
+            ("new\nline", InvalidPlayerScreenName::InvalidCharacters),
+        ] {
+            let availability = service.screen_name_availability(screen_name).await.unwrap();
+
+            assert_eq!(
+                availability,
+                ScreenNameAvailability::unavailable(reason.to_string())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_notify_enqueues_unread_notification() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_create_notification()
+            .withf(|player_id, notification| {
+                player_id == &PlayerId::test()
+                    && notification.kind == NotificationKind::MatchFound
+                    && notification.message == "Match found"
+                    && !notification.read
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let service = service_with_players_db(players_db);
+
+        let notification = service
+            .notify(
+                &PlayerId::test(),
+                NotificationKind::MatchFound,
+                "Match found",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(notification.kind, NotificationKind::MatchFound);
+        assert!(!notification.read);
+    }
+
+    #[tokio::test]
+    async fn players_service_notifications_returns_page_of_requested_size() {
+        let notifications = (0..3)
+            .map(|_| Notification::new(NotificationKind::Challenged, "Challenged".into()))
+            .collect::<Vec<_>>();
+        let found = notifications.clone();
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_notifications()
+            .with(
+                eq(PlayerId::test()),
+                eq(true),
+                eq(Some(NotificationId::test())),
+                eq(3),
+            )
+            .times(1)
+            .returning(move |_, _, _, _| Box::pin(std::future::ready(Ok(found.clone()))));
+        let service = service_with_players_db(players_db);
+
+        let page = service
+            .notifications(
+                &PlayerId::test(),
+                &NotificationsQuery {
+                    unread_only: true,
+                    before: Some(NotificationId::test()),
+                    limit: Some(2),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(page.notifications, notifications[..2]);
+        assert_eq!(page.next_before, Some(notifications[1].id));
+    }
+
+    #[tokio::test]
+    async fn players_service_notifications_clamps_limit() {
+        for (limit, expected_db_limit) in [
+            (None, NotificationsPage::DEFAULT_LIMIT + 1),
+            (Some(0), 2),
+            (Some(1000), NotificationsPage::MAX_LIMIT + 1),
+        ] {
+            let mut players_db = MockPlayersDb::new();
+            players_db
+                .expect_find_notifications()
+                .with(
+                    eq(PlayerId::test()),
+                    eq(false),
+                    eq(None),
+                    eq(expected_db_limit),
+                )
+                .times(1)
+                .returning(|_, _, _, _| Box::pin(async { Ok(vec![]) }));
+            let service = service_with_players_db(players_db);
+
+            let page = service
+                .notifications(
+                    &PlayerId::test(),
+                    &NotificationsQuery {
+                        limit,
+                        ..NotificationsQuery::default()
+                    },
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(page.next_before, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_mark_notifications_read_marks_given_notifications() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_mark_notifications_read()
+            .withf(|player_id, ids| {
+                player_id == &PlayerId::test() && ids == [NotificationId::test()]
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        let service = service_with_players_db(players_db);
+
+        service
+            .mark_notifications_read(&PlayerId::test(), &[NotificationId::test()])
+            .await
+            .unwrap();
+        service
+            .mark_notifications_read(&PlayerId::test(), &[])
+            .await
+            .unwrap();
+    }
+
+    fn google_service_with_platform_audiences(
+        aud: &'static str,
+        players_db: MockPlayersDb,
+        jwt_service: MockJwtService,
+    ) -> PlayersServiceDefault<MockPlayersDb, MockIdTokenVerifier, MockJwtService> {
+        let mut google_id_token_verifier = MockIdTokenVerifier::new();
+        google_id_token_verifier
+            .expect_verify::<ThirdPartyIdTokenClaims>()
+            .returning(move |_| {
+                Box::pin(async move {
+                    match aud {
+                        "unknown_aud" => Err(IdTokenVerifierError::Validation(
+                            ValidationError::ValidationFailed(
+                                jsonwebtoken::errors::ErrorKind::InvalidAudience.into(),
+                            ),
+                        )),
+                        aud => Ok(ThirdPartyIdTokenClaims {
+                            aud: OneOrVec::One(Aud::new(aud)),
+                            ..ThirdPartyIdTokenClaims::test()
+                        }),
+                    }
+                })
+            });
+
+        PlayersServiceDefault {
+            players_db,
+            id_token_verifiers: HashMap::from([(
+                ThirdPartySignInProvider::Google,
+                google_id_token_verifier,
+            )]),
+            jwt_service,
+            id_token_replay_guard: None,
+            id_token_verification_limit: IdTokenVerificationLimit::test(),
+            id_token_platform_audiences: IdTokenPlatformAudiencesConfig {
+                web: vec![Aud::new("web_aud")],
+                android: vec![Aud::new("android_aud")],
+                ios: vec![Aud::new("ios_aud")],
+            },
+        }
+    }
+
+    fn google_sign_in_request(platform: ClientPlatform) -> SignInRequest {
+        SignInRequest::Google {
+            id_token: IdToken::test(),
+            platform: Some(platform),
+        }
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_rejects_id_token_of_known_wrong_platform_client() {
+        let service = google_service_with_platform_audiences(
+            "ios_aud",
+            MockPlayersDb::new(),
+            MockJwtService::new(),
+        );
+
+        let result = service
+            .sign_in(&google_sign_in_request(ClientPlatform::Android))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PlayersError::IdTokenWrongPlatform(ClientPlatform::Ios))
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_rejects_id_token_of_unknown_client_generically() {
+        let service = google_service_with_platform_audiences(
+            "unknown_aud",
+            MockPlayersDb::new(),
+            MockJwtService::new(),
+        );
+
+        let result = service
+            .sign_in(&google_sign_in_request(ClientPlatform::Android))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PlayersError::IdToken(IdTokenVerifierError::Validation(
+                ValidationError::ValidationFailed(_)
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn players_service_sign_in_accepts_id_token_of_matching_or_unspecified_platform() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_with_sign_in_method()
+            .returning(|_| Box::pin(async { Ok(Player::test()) }));
+        players_db
+            .expect_create_refresh_token()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut jwt_service = MockJwtService::new();
+        jwt_service
+            .expect_create_token_pair()
+            .returning(|_, _, _| Ok(TokenPair::test()));
+        let service =
+            google_service_with_platform_audiences("android_aud", players_db, jwt_service);
+
+        let matching = service
+            .sign_in(&google_sign_in_request(ClientPlatform::Android))
+            .await;
+        let unspecified = service.sign_in(&SignInRequest::test_google()).await;
+
+        assert!(matching.is_ok());
+        assert!(unspecified.is_ok());
+    }
 }
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Client settings of a player stored server-side. Unknown fields are rejected.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerPreferences {
+    /// [Theme] of the client.
+    pub theme: Theme,
+
+    /// Locale of the client, e.g. `en` or `uk-UA`.
+    pub locale: String,
+
+    /// Whether the player wants to get notifications.
+    pub notifications: bool,
+}
+
+impl Default for PlayerPreferences {
+    fn default() -> PlayerPreferences {
+        PlayerPreferences {
+            theme: Theme::default(),
+            locale: "en".into(),
+            notifications: true,
+        }
+    }
+}
+
+impl PlayerPreferences {
+    #[cfg(test)]
+    /// Returns test [PlayerPreferences].
+    pub fn test() -> PlayerPreferences {
+        PlayerPreferences {
+            theme: Theme::Dark,
+            locale: "uk-UA".into(),
+            notifications: false,
+        }
+    }
+}
+
+/// Color theme of the client.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Theme {
+    /// Follows the theme of the system.
+    #[default]
+    System,
+
+    /// Light theme.
+    Light,
+
+    /// Dark theme.
+    Dark,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn player_preferences_json_snapshot() {
+        insta::assert_json_snapshot!(&PlayerPreferences::default());
+    }
+}
@@ -0,0 +1,141 @@
+use crate::players::error::PlayersError;
+use crate::players::player::{Player, PlayerId};
+use crate::players::players_db::{PlayersDb, SlowQueryLoggingPlayersDb};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Whether a [Player] is online.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Presence {
+    /// The [Player] has made an authenticated request within [Presence::ONLINE_WINDOW].
+    Online,
+
+    /// The [Player] has not been active recently.
+    Offline,
+}
+
+impl Presence {
+    /// How long a [Player] stays online after their last authenticated request.
+    pub const ONLINE_WINDOW: TimeDelta = TimeDelta::minutes(5);
+
+    /// Returns the [Presence] of the given [Player] at the given time.
+    pub fn of(player: &Player, now: DateTime<Utc>) -> Presence {
+        let recently_seen = player
+            .last_seen_at
+            .is_some_and(|last_seen_at| last_seen_at > now - Presence::ONLINE_WINDOW);
+
+        if recently_seen {
+            Presence::Online
+        } else {
+            Presence::Offline
+        }
+    }
+}
+
+/// Tells which [Player]s are online, based on when they were last seen.
+#[cfg_attr(test, mockall::automock)]
+pub trait PresenceService {
+    /// Returns the [Presence] of the [Player] with the given [PlayerId].
+    /// Fails with [PlayersError::ProfileNotFound] if there is no such [Player].
+    fn presence(
+        &self,
+        player_id: &PlayerId,
+    ) -> impl Future<Output = Result<Presence, PlayersError>> + Send;
+
+    /// Returns the number of [Presence::Online] [Player]s.
+    fn online_count(&self) -> impl Future<Output = Result<i64, PlayersError>> + Send;
+}
+
+/// Default [PresenceService] implementation.
+#[derive(Clone)]
+pub struct PresenceServiceDefault<D = SlowQueryLoggingPlayersDb> {
+    /// [PlayersDb] keeping track of when [Player]s were last seen.
+    players_db: D,
+}
+
+impl<D> PresenceServiceDefault<D> {
+    /// Creates a new [PresenceServiceDefault] with the given [PlayersDb].
+    pub fn new(players_db: D) -> PresenceServiceDefault<D> {
+        PresenceServiceDefault { players_db }
+    }
+}
+
+impl<D: PlayersDb + Sync> PresenceService for PresenceServiceDefault<D> {
+    async fn presence(&self, player_id: &PlayerId) -> Result<Presence, PlayersError> {
+        let player = match self.players_db.find_player_by_id(player_id).await {
+            Ok(player) => player,
+            Err(PlayersError::PlayerNotFound) => Err(PlayersError::ProfileNotFound)?,
+            Err(e) => Err(e)?,
+        };
+
+        Ok(Presence::of(&player, Utc::now()))
+    }
+
+    async fn online_count(&self) -> Result<i64, PlayersError> {
+        self.players_db
+            .count_players_seen_since(Utc::now() - Presence::ONLINE_WINDOW)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::players_db::MockPlayersDb;
+    use mockall::predicate::eq;
+
+    #[test]
+    fn presence_of_player_seen_recently_is_online() {
+        let now = Utc::now();
+        let seen = |ago| Player {
+            last_seen_at: Some(now - ago),
+            ..Player::test()
+        };
+
+        assert_eq!(
+            Presence::of(&seen(TimeDelta::minutes(1)), now),
+            Presence::Online
+        );
+        assert_eq!(
+            Presence::of(&seen(Presence::ONLINE_WINDOW), now),
+            Presence::Offline
+        );
+        assert_eq!(Presence::of(&Player::test(), now), Presence::Offline);
+    }
+
+    #[tokio::test]
+    async fn presence_service_uses_last_seen_of_players() {
+        let mut players_db = MockPlayersDb::new();
+        players_db
+            .expect_find_player_by_id()
+            .with(eq(PlayerId::test()))
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(Player {
+                        last_seen_at: Some(Utc::now()),
+                        ..Player::test()
+                    })
+                })
+            });
+        players_db
+            .expect_find_player_by_id()
+            .returning(|_| Box::pin(async { Err(PlayersError::PlayerNotFound) }));
+        players_db
+            .expect_count_players_seen_since()
+            .withf(|since| {
+                let window = Utc::now() - *since;
+                window >= Presence::ONLINE_WINDOW
+                    && window < Presence::ONLINE_WINDOW + TimeDelta::seconds(1)
+            })
+            .returning(|_| Box::pin(async { Ok(3) }));
+        let service = PresenceServiceDefault::new(players_db);
+
+        let presence = service.presence(&PlayerId::test()).await.unwrap();
+        let unknown = service.presence(&PlayerId::random()).await;
+        let online_count = service.online_count().await.unwrap();
+
+        assert_eq!(presence, Presence::Online);
+        assert!(matches!(unknown, Err(PlayersError::ProfileNotFound)));
+        assert_eq!(online_count, 3);
+    }
+}
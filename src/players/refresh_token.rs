@@ -0,0 +1,122 @@
+use crate::players::player::PlayerId;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Opaque token exchanged for a new [crate::players::jwt_service::AuthToken] once it expires.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefreshToken(pub String);
+
+impl RefreshToken {
+    /// Size of the random part of a [RefreshToken] in bytes.
+    const SIZE: usize = 32;
+
+    /// Generates a new random [RefreshToken].
+    pub fn random() -> RefreshToken {
+        let mut bytes = [0u8; RefreshToken::SIZE];
+        OsRng.fill_bytes(&mut bytes);
+
+        RefreshToken(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+
+    #[cfg(test)]
+    /// Returns a test [RefreshToken].
+    pub fn test() -> RefreshToken {
+        RefreshToken("refresh_token".into())
+    }
+}
+
+/// SHA-256 hash of a [RefreshToken], the only form a [RefreshToken] is stored in.
+#[derive(Debug, Clone, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct RefreshTokenHash(pub Vec<u8>);
+
+impl RefreshTokenHash {
+    /// Hashes the given [RefreshToken].
+    pub fn new(refresh_token: &RefreshToken) -> RefreshTokenHash {
+        RefreshTokenHash(Sha256::digest(refresh_token.0.as_bytes()).to_vec())
+    }
+}
+
+/// ID of a chain of [RefreshToken]s rotated from the same sign in.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct RefreshTokenFamilyId(pub Uuid);
+
+impl RefreshTokenFamilyId {
+    /// Generates a new random [RefreshTokenFamilyId].
+    pub fn random() -> RefreshTokenFamilyId {
+        RefreshTokenFamilyId(Uuid::now_v7())
+    }
+
+    #[cfg(test)]
+    /// Returns a test [RefreshTokenFamilyId].
+    pub fn test() -> RefreshTokenFamilyId {
+        RefreshTokenFamilyId(Uuid::from_u128(987654321))
+    }
+}
+
+/// [RefreshToken] as stored in the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredRefreshToken {
+    /// [RefreshTokenHash] of the [RefreshToken].
+    pub hash: RefreshTokenHash,
+
+    /// [RefreshTokenFamilyId] of the chain the [RefreshToken] belongs to.
+    pub family_id: RefreshTokenFamilyId,
+
+    /// [PlayerId] the [RefreshToken] is issued for.
+    pub player_id: PlayerId,
+
+    /// When the [RefreshToken] expires.
+    pub expires_at: DateTime<Utc>,
+
+    /// Whether the [RefreshToken] has already been exchanged for a new one.
+    pub rotated: bool,
+
+    /// Whether the [RefreshToken] chain has been revoked.
+    pub revoked: bool,
+}
+
+impl StoredRefreshToken {
+    #[cfg(test)]
+    /// Returns a test [StoredRefreshToken] of [RefreshToken::test].
+    pub fn test() -> StoredRefreshToken {
+        StoredRefreshToken {
+            hash: RefreshTokenHash::new(&RefreshToken::test()),
+            family_id: RefreshTokenFamilyId::test(),
+            player_id: PlayerId::test(),
+            expires_at: DateTime::from_timestamp(4102444800, 0).unwrap(),
+            rotated: false,
+            revoked: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_token_random_is_unique() {
+        let first = RefreshToken::random();
+        let second = RefreshToken::random();
+
+        assert_ne!(first, second);
+        assert_eq!(first.0.len(), RefreshToken::SIZE * 2);
+    }
+
+    #[test]
+    fn refresh_token_hash_is_stable() {
+        assert_eq!(
+            RefreshTokenHash::new(&RefreshToken::test()),
+            RefreshTokenHash::new(&RefreshToken::test())
+        );
+        assert_ne!(
+            RefreshTokenHash::new(&RefreshToken::test()),
+            RefreshTokenHash::new(&RefreshToken::random())
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::players::page::PageItem;
+use crate::players::player::PlayerId;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// ID of a [Report].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct ReportId(pub Uuid);
+
+impl ReportId {
+    /// Generates a new random [ReportId]. [ReportId]s are time ordered, newer ones are greater.
+    pub fn random() -> ReportId {
+        ReportId(Uuid::now_v7())
+    }
+
+    #[cfg(test)]
+    /// Returns a test [ReportId].
+    pub fn test() -> ReportId {
+        ReportId(Uuid::from_u128(6655443322))
+    }
+}
+
+/// Moderation status of a [Report].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "report_status")]
+pub enum ReportStatus {
+    /// The [Report] waits for a moderator in the queue.
+    Open,
+
+    /// A moderator has dealt with the [Report].
+    Resolved,
+}
+
+/// Report of a player's misconduct by another player.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Report {
+    /// [ReportId] of the report.
+    pub id: ReportId,
+
+    /// [PlayerId] of the reporting player, [None] if they have deleted their account since.
+    pub reporter_id: Option<PlayerId>,
+
+    /// [PlayerId] of the reported player, kept if they have deleted their account since.
+    pub reported_id: PlayerId,
+
+    /// ID of the game the misconduct happened in, if any.
+    pub game_id: Option<Uuid>,
+
+    /// What the reported player did, in the words of the reporter.
+    pub reason: String,
+
+    /// When the report was made.
+    pub created_at: DateTime<Utc>,
+
+    /// [ReportStatus] of the report.
+    pub status: ReportStatus,
+}
+
+impl Report {
+    /// Max size of a [Report::reason] in characters.
+    pub const MAX_REASON_SIZE: usize = 1000;
+
+    /// Max number of [Report]s a player can make within [Report::RATE_LIMIT_WINDOW].
+    pub const MAX_PER_WINDOW: i64 = 5;
+
+    /// Window the [Report]s of a player are counted within.
+    pub const RATE_LIMIT_WINDOW: TimeDelta = TimeDelta::hours(1);
+
+    /// Creates a new [ReportStatus::Open] [Report] of the given reported player by the given
+    /// reporter in the given game, if any, with the given reason.
+    pub fn new(
+        reporter_id: PlayerId,
+        reported_id: PlayerId,
+        game_id: Option<Uuid>,
+        reason: String,
+    ) -> Report {
+        Report {
+            id: ReportId::random(),
+            reporter_id: Some(reporter_id),
+            reported_id,
+            game_id,
+            reason,
+            created_at: Utc::now(),
+            status: ReportStatus::Open,
+        }
+    }
+
+    /// Whether the given reason is neither blank nor longer than [Report::MAX_REASON_SIZE].
+    pub fn is_valid_reason(reason: &str) -> bool {
+        !reason.trim().is_empty() && reason.chars().count() <= Report::MAX_REASON_SIZE
+    }
+
+    #[cfg(test)]
+    /// Returns a test [Report].
+    pub fn test() -> Report {
+        Report {
+            id: ReportId::test(),
+            reporter_id: Some(PlayerId::test()),
+            reported_id: crate::players::friendship::Friendship::test().addressee_id,
+            game_id: None,
+            reason: "Insulted me in chat".into(),
+            created_at: DateTime::from_timestamp(1735689600, 0).unwrap(),
+            status: ReportStatus::Open,
+        }
+    }
+}
+
+impl PageItem for Report {
+    type Cursor = ReportId;
+
+    fn cursor(&self) -> ReportId {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_json_snapshot() {
+        insta::assert_json_snapshot!(&Report::test());
+    }
+
+    #[test]
+    fn report_is_valid_reason_rejects_blank_and_too_long_reasons() {
+        assert!(Report::is_valid_reason("Cheating"));
+        assert!(Report::is_valid_reason(
+            &"ы".repeat(Report::MAX_REASON_SIZE)
+        ));
+        assert!(!Report::is_valid_reason(" \n"));
+        assert!(!Report::is_valid_reason(
+            &"a".repeat(Report::MAX_REASON_SIZE + 1)
+        ));
+    }
+}
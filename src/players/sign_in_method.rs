@@ -1,10 +1,22 @@
+use crate::players::password::PasswordHash;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Supported sign-in methods.
 #[derive(Debug, Clone, PartialEq)]
 pub enum SignInMethod {
     /// Sign in method using third party.
     ThirdParty(ThirdPartySignInMethod),
+
+    /// Sign in method using email and password.
+    EmailPassword(EmailPasswordSignInMethod),
+
+    /// Guest sign in method bound to a device, which can later be linked to a
+    /// [ThirdPartySignInMethod].
+    Anonymous {
+        /// [DeviceId] the guest signs in from.
+        device_id: DeviceId,
+    },
 }
 
 impl SignInMethod {
@@ -16,6 +28,46 @@ impl SignInMethod {
             user_id: ThirdPartySignInUserId::test(),
         })
     }
+
+    #[cfg(test)]
+    /// Returns a test [SignInMethod] with [ThirdPartySignInProvider::Apple].
+    pub fn test_apple() -> SignInMethod {
+        SignInMethod::ThirdParty(ThirdPartySignInMethod {
+            provider: ThirdPartySignInProvider::Apple,
+            user_id: ThirdPartySignInUserId::test(),
+        })
+    }
+
+    #[cfg(test)]
+    /// Returns a test [SignInMethod::Anonymous].
+    pub fn test_anonymous() -> SignInMethod {
+        SignInMethod::Anonymous {
+            device_id: DeviceId::test(),
+        }
+    }
+}
+
+/// [SignInMethod] linked to a [Player](crate::players::player::Player) as shown to them, i.e.
+/// without any secrets.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LinkedSignInMethod {
+    /// Linked account of a [ThirdPartySignInProvider].
+    ThirdParty {
+        /// [ThirdPartySignInProvider] of the account.
+        provider: ThirdPartySignInProvider,
+    },
+
+    /// Registered [Email] to sign in with a password.
+    EmailPassword {
+        /// Registered [Email].
+        email: Email,
+    },
+
+    /// Guest device.
+    Anonymous {
+        /// [DeviceId] of the guest's device.
+        device_id: DeviceId,
+    },
 }
 
 /// Third-party sign-in method.
@@ -29,15 +81,32 @@ pub struct ThirdPartySignInMethod {
 }
 
 /// Supported [ThirdPartySignInMethod] providers.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, sqlx::Type)]
 #[sqlx(type_name = "third_party_sign_in_provider")]
 pub enum ThirdPartySignInProvider {
     /// Google.
     Google,
+
+    /// Apple.
+    Apple,
+}
+
+/// Platform of the client signing in with a [ThirdPartySignInProvider].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ClientPlatform {
+    /// Web client.
+    Web,
+
+    /// Android client.
+    Android,
+
+    /// iOS client.
+    Ios,
 }
 
 /// User ID within the [ThirdPartySignInProvider].
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
 pub struct ThirdPartySignInUserId(pub String);
 
 impl ThirdPartySignInUserId {
@@ -47,3 +116,115 @@ impl ThirdPartySignInUserId {
         ThirdPartySignInUserId("test-user-id".into())
     }
 }
+
+/// Client generated ID of a guest's device. It is not a secret, so guests are never looked up by
+/// it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct DeviceId(pub String);
+
+impl DeviceId {
+    /// Max size of a [DeviceId] in characters.
+    pub const MAX_SIZE: usize = 128;
+
+    /// Whether this [DeviceId] is neither blank nor longer than [DeviceId::MAX_SIZE] characters.
+    pub fn is_valid(&self) -> bool {
+        !self.0.trim().is_empty() && self.0.chars().count() <= DeviceId::MAX_SIZE
+    }
+
+    #[cfg(test)]
+    /// Returns a test [DeviceId].
+    pub fn test() -> DeviceId {
+        DeviceId("test-device-id".into())
+    }
+}
+
+/// Email and password sign-in method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailPasswordSignInMethod {
+    /// [Email] the player signs in with.
+    pub email: Email,
+
+    /// [PasswordHash] of the player's password.
+    pub password_hash: PasswordHash,
+}
+
+/// Normalized (trimmed and lowercased) email address.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct Email(String);
+
+impl Email {
+    /// The maximum size of an [Email] in bytes.
+    pub const MAX_SIZE: usize = 254;
+
+    #[cfg(test)]
+    /// Returns a test [Email].
+    pub fn test() -> Email {
+        Email("player@example.com".into())
+    }
+}
+
+/// [Email] validation error.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, thiserror::Error)]
+#[error("invalid email")]
+pub struct InvalidEmail;
+
+impl FromStr for Email {
+    type Err = InvalidEmail;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+
+        let valid = s.len() <= Email::MAX_SIZE
+            && !s.contains(char::is_whitespace)
+            && s.split_once('@').is_some_and(|(local, domain)| {
+                !local.is_empty() && !domain.is_empty() && !domain.contains('@')
+            });
+        if !valid {
+            Err(InvalidEmail)?
+        }
+
+        Ok(Email(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn email_from_str_normalizes_valid_emails() {
+        assert_eq!(
+            Email::from_str("  Player@Example.COM "),
+            Ok(Email("player@example.com".into()))
+        );
+    }
+
+    #[test]
+    fn email_from_str_rejects_invalid_emails() {
+        for email in [
+            "",
+            "   ",
+            "player",
+            "@example.com",
+            "player@",
+            "a@b@c",
+            "pla yer@x.com",
+        ] {
+            assert_eq!(Email::from_str(email), Err(InvalidEmail), "{email}");
+        }
+
+        let too_long = format!("{}@example.com", "a".repeat(Email::MAX_SIZE));
+        assert_eq!(Email::from_str(&too_long), Err(InvalidEmail));
+    }
+
+    #[test]
+    fn device_id_is_valid_if_neither_blank_nor_too_long() {
+        assert!(DeviceId::test().is_valid());
+        assert!(DeviceId("d".repeat(DeviceId::MAX_SIZE)).is_valid());
+        assert!(!DeviceId(String::new()).is_valid());
+        assert!(!DeviceId("   ".into()).is_valid());
+        assert!(!DeviceId("d".repeat(DeviceId::MAX_SIZE + 1)).is_valid());
+    }
+}
@@ -0,0 +1,279 @@
+use crate::app_state::AppState;
+use crate::config::SignInRateLimitConfig;
+use crate::players::error::PlayersError;
+use axum::RequestPartsExt;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Max number of IPs tracked at once, the ones with the oldest windows are forgotten first.
+const MAX_TRACKED_IPS: usize = 100_000;
+
+/// Limits the number of sign-ins per client IP within a fixed window.
+pub struct SignInRateLimiter {
+    /// [SignInRateLimitConfig] of the limiter.
+    config: SignInRateLimitConfig,
+
+    /// Max number of IPs tracked at once, see [MAX_TRACKED_IPS].
+    max_tracked_ips: usize,
+
+    /// [Windows] of the tracked client IPs.
+    windows: Mutex<Windows>,
+}
+
+/// Current windows of the tracked client IPs.
+#[derive(Default)]
+struct Windows {
+    /// Start of the current window and the number of sign-ins within it per client IP.
+    counts: HashMap<IpAddr, (Instant, u32)>,
+
+    /// Client IPs of the [Windows::counts] in the order their windows have started.
+    started: VecDeque<(IpAddr, Instant)>,
+}
+
+impl SignInRateLimiter {
+    /// Creates a new [SignInRateLimiter] with the given [SignInRateLimitConfig].
+    pub fn new(config: SignInRateLimitConfig) -> SignInRateLimiter {
+        SignInRateLimiter {
+            config,
+            max_tracked_ips: MAX_TRACKED_IPS,
+            windows: Mutex::new(Windows::default()),
+        }
+    }
+
+    /// Counts a sign-in from the given IP. Fails with [PlayersError::SignInRateLimited] if the
+    /// IP has exceeded [SignInRateLimitConfig::max_requests] within the current window.
+    pub fn check(&self, ip: IpAddr) -> Result<(), PlayersError> {
+        let now = Instant::now();
+        let window = self.config.window;
+
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let Windows { counts, started } = &mut *windows;
+
+        // Windows start in order, so the expired ones are at the front.
+        while let Some((expired_ip, _)) = started
+            .front()
+            .filter(|(_, started_at)| now.duration_since(*started_at) >= window)
+        {
+            counts.remove(expired_ip);
+            started.pop_front();
+        }
+
+        if !counts.contains_key(&ip) {
+            if counts.len() >= self.max_tracked_ips {
+                // Still full with active windows, forget the one started first.
+                if let Some((oldest_ip, _)) = started.pop_front() {
+                    counts.remove(&oldest_ip);
+                }
+            }
+            counts.insert(ip, (now, 0));
+            started.push_back((ip, now));
+        }
+
+        let (started_at, count) = counts.entry(ip).or_insert((now, 0));
+        if *count >= self.config.max_requests {
+            let retry_after = window.saturating_sub(now.duration_since(*started_at));
+            Err(PlayersError::SignInRateLimited(
+                retry_after.max(Duration::from_secs(1)),
+            ))?
+        }
+
+        *count += 1;
+
+        Ok(())
+    }
+
+    /// Returns the client IP of the request sent by the given peer. The
+    /// [SignInRateLimitConfig::client_ip_header] is only trusted if the peer is one of the
+    /// [SignInRateLimitConfig::trusted_proxies].
+    fn client_ip(&self, parts: &Parts, peer: &SocketAddr) -> IpAddr {
+        if !self.config.trusted_proxies.contains(&peer.ip()) {
+            return peer.ip();
+        }
+
+        parts
+            .headers
+            .get(&self.config.client_ip_header)
+            .and_then(|header_value| header_value.to_str().ok())
+            .and_then(|ips| ips.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse().ok())
+            .unwrap_or(peer.ip())
+    }
+}
+
+/// Extractor counting a sign-in against the [SignInRateLimiter] of the [AppState], rejecting it
+/// with [PlayersError::SignInRateLimited] if the client IP has exceeded the limit.
+pub struct SignInRateLimit;
+
+impl<S: AppState> FromRequestParts<S> for SignInRateLimit {
+    type Rejection = PlayersError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(limiter) = state.sign_in_rate_limiter() else {
+            return Ok(SignInRateLimit);
+        };
+        let Ok(ConnectInfo(peer)) = parts.extract::<ConnectInfo<SocketAddr>>().await else {
+            return Ok(SignInRateLimit);
+        };
+
+        limiter.check(limiter.client_ip(parts, &peer))?;
+
+        Ok(SignInRateLimit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_error::ApiError;
+    use crate::app_state::MockAppState;
+    use axum::Router;
+    use axum::extract::connect_info::MockConnectInfo;
+    use axum::http::StatusCode;
+    use axum::http::header::RETRY_AFTER;
+    use axum::routing::post;
+    use axum_test::TestServer;
+    use std::sync::Arc;
+
+    const TRUSTED_PROXY: [u8; 4] = [10, 0, 0, 1];
+    const MAX_REQUESTS: u32 = 3;
+
+    fn server(peer: [u8; 4]) -> anyhow::Result<TestServer> {
+        let limiter = SignInRateLimiter::new(SignInRateLimitConfig {
+            enabled: true,
+            max_requests: MAX_REQUESTS,
+            window: Duration::from_secs(60),
+            trusted_proxies: vec![TRUSTED_PROXY.into()],
+            ..SignInRateLimitConfig::default()
+        });
+        let state = Arc::new(MockAppState::default().with_sign_in_rate_limiter(Some(limiter)));
+
+        let router = Router::new()
+            .route(
+                "/players/sign_in",
+                post(|_: SignInRateLimit| async { StatusCode::OK }),
+            )
+            .with_state(state)
+            .layer(MockConnectInfo(SocketAddr::from((peer, 1234))));
+
+        TestServer::new(router)
+    }
+
+    #[test]
+    fn check_stays_within_max_tracked_ips() {
+        let limiter = SignInRateLimiter {
+            max_tracked_ips: 2,
+            ..SignInRateLimiter::new(SignInRateLimitConfig {
+                enabled: true,
+                max_requests: 1,
+                window: Duration::from_secs(60),
+                ..SignInRateLimitConfig::default()
+            })
+        };
+        let ip = |i: u8| IpAddr::from([203, 0, 113, i]);
+
+        for i in 0..5 {
+            assert!(limiter.check(ip(i)).is_ok());
+        }
+
+        let windows = limiter.windows.lock().unwrap();
+        assert_eq!(windows.counts.len(), 2);
+        assert_eq!(windows.started.len(), 2);
+        assert!(windows.counts.contains_key(&ip(3)));
+        assert!(windows.counts.contains_key(&ip(4)));
+    }
+
+    #[test]
+    fn check_forgets_expired_windows() {
+        let limiter = SignInRateLimiter::new(SignInRateLimitConfig {
+            enabled: true,
+            max_requests: 1,
+            window: Duration::from_millis(1),
+            ..SignInRateLimitConfig::default()
+        });
+
+        for i in 0..5 {
+            assert!(limiter.check(IpAddr::from([203, 0, 113, i])).is_ok());
+        }
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(limiter.check(IpAddr::from([203, 0, 113, 0])).is_ok());
+
+        let windows = limiter.windows.lock().unwrap();
+        assert_eq!(windows.counts.len(), 1);
+        assert_eq!(windows.started.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn sign_in_rate_limit_rejects_requests_over_the_limit() -> anyhow::Result<()> {
+        let server = server([10, 0, 0, 2])?;
+
+        for _ in 0..MAX_REQUESTS {
+            server
+                .post("/players/sign_in")
+                .await
+                .assert_status(StatusCode::OK);
+        }
+
+        let response = server.post("/players/sign_in").await;
+
+        response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.header(RETRY_AFTER), "60");
+        assert_eq!(
+            response.json::<ApiError>().id,
+            PlayersError::SIGN_IN_RATE_LIMITED.id
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sign_in_rate_limit_limits_client_ips_separately() -> anyhow::Result<()> {
+        let server = server(TRUSTED_PROXY)?;
+
+        for _ in 0..MAX_REQUESTS {
+            server
+                .post("/players/sign_in")
+                .add_header("x-forwarded-for", "203.0.113.1")
+                .await
+                .assert_status(StatusCode::OK);
+        }
+
+        server
+            .post("/players/sign_in")
+            .add_header("x-forwarded-for", "203.0.113.1")
+            .await
+            .assert_status(StatusCode::TOO_MANY_REQUESTS);
+        server
+            .post("/players/sign_in")
+            .add_header("x-forwarded-for", "203.0.113.1, 203.0.113.2")
+            .await
+            .assert_status(StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sign_in_rate_limit_ignores_client_ip_header_of_untrusted_peers() -> anyhow::Result<()>
+    {
+        let server = server([10, 0, 0, 2])?;
+
+        for i in 0..MAX_REQUESTS {
+            server
+                .post("/players/sign_in")
+                .add_header("x-forwarded-for", format!("203.0.113.{i}"))
+                .await
+                .assert_status(StatusCode::OK);
+        }
+
+        server
+            .post("/players/sign_in")
+            .add_header("x-forwarded-for", "203.0.113.100")
+            .await
+            .assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::api_error::{ApiError, ErrorCode};
+use axum::Router;
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::response::Response;
+
+/// [ErrorCode] of requests to unknown paths.
+pub const NOT_FOUND: ErrorCode = ErrorCode::new("routing", 0);
+
+/// [ErrorCode] of requests with a method that the path doesn't support.
+pub const METHOD_NOT_ALLOWED: ErrorCode = ErrorCode::new("routing", 1);
+
+/// Wraps the given [Router] so that requests to unknown paths and with unsupported methods are
+/// rejected with an [ApiError] instead of an empty body. `405 Method Not Allowed` responses keep
+/// their `Allow` header.
+pub fn with_fallbacks(router: Router) -> Router {
+    router
+        .fallback(not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+}
+
+/// Fallback handler of unknown paths.
+async fn not_found(request: Request) -> Response {
+    ApiError::new(NOT_FOUND, format!("no route for {}", request.uri().path()))
+        .into_response(StatusCode::NOT_FOUND)
+}
+
+/// Fallback handler of unsupported methods, axum adds the `Allow` header to its response.
+async fn method_not_allowed(request: Request) -> Response {
+    ApiError::new(
+        METHOD_NOT_ALLOWED,
+        format!("method {} is not allowed", request.method()),
+    )
+    .into_response(StatusCode::METHOD_NOT_ALLOWED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::ALLOW;
+    use axum::routing::{get, post};
+    use axum_test::TestServer;
+    use tower_http::cors::CorsLayer;
+
+    fn server() -> anyhow::Result<TestServer> {
+        let router = Router::new()
+            .route("/health", get(|| async { StatusCode::OK }))
+            .merge(
+                Router::new()
+                    .nest(
+                        "/players",
+                        Router::new().route("/sign_in", post(|| async { StatusCode::OK })),
+                    )
+                    .layer(CorsLayer::permissive()),
+            );
+
+        TestServer::new(with_fallbacks(router))
+    }
+
+    #[tokio::test]
+    async fn with_fallbacks_rejects_unknown_paths_with_api_error() -> anyhow::Result<()> {
+        let server = server()?;
+
+        let response = server.get("/players/unknown/path").await;
+
+        response.assert_status(StatusCode::NOT_FOUND);
+        let error = response.json::<ApiError>();
+        assert_eq!(error.module, NOT_FOUND.module);
+        assert_eq!(error.id, NOT_FOUND.id);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_fallbacks_rejects_unsupported_methods_with_api_error() -> anyhow::Result<()> {
+        let server = server()?;
+
+        for (path, allow) in [("/health", "GET,HEAD"), ("/players/sign_in", "POST")] {
+            let response = server.put(path).await;
+
+            response.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+            assert_eq!(response.header(ALLOW), allow, "{path}");
+            let error = response.json::<ApiError>();
+            assert_eq!(error.module, METHOD_NOT_ALLOWED.module);
+            assert_eq!(error.id, METHOD_NOT_ALLOWED.id);
+        }
+
+        Ok(())
+    }
+}
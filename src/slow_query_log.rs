@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Awaits the given query future and logs a warning with the `query_name` and the elapsed time if
+/// it took longer than `threshold`. Query parameters are never logged as they may be sensitive.
+pub async fn log_if_slow<F: Future>(query_name: &str, threshold: Duration, query: F) -> F::Output {
+    let start = Instant::now();
+    let output = query.await;
+    let elapsed = start.elapsed();
+
+    if elapsed > threshold {
+        warn!(
+            query_name,
+            elapsed_ms = elapsed.as_millis() as u64,
+            threshold_ms = threshold.as_millis() as u64,
+            "slow query"
+        );
+    }
+
+    output
+}
+
+#[cfg(test)]
+/// Captures logs emitted by the current thread while the returned guard is alive.
+pub fn capture_logs() -> (CapturedLogs, tracing::subscriber::DefaultGuard) {
+    let logs = CapturedLogs::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(logs.clone())
+        .with_ansi(false)
+        .finish();
+
+    (logs, tracing::subscriber::set_default(subscriber))
+}
+
+#[cfg(test)]
+/// Logs captured with [capture_logs].
+#[derive(Clone, Default)]
+pub struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl CapturedLogs {
+    /// Returns the captured logs as a string.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLogs {
+    type Writer = CapturedLogs;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn log_if_slow_warns_about_slow_queries() {
+        let (logs, _guard) = capture_logs();
+
+        let output = log_if_slow("slow_test_query", Duration::from_millis(1), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            42
+        })
+        .await;
+
+        assert_eq!(output, 42);
+        let logs = logs.contents();
+        assert!(logs.contains("WARN"));
+        assert!(logs.contains("slow query"));
+        assert!(logs.contains("slow_test_query"));
+    }
+
+    #[tokio::test]
+    async fn log_if_slow_does_not_warn_about_fast_queries() {
+        let (logs, _guard) = capture_logs();
+
+        log_if_slow("fast_test_query", Duration::from_secs(10), async {}).await;
+
+        assert!(logs.contents().is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::config::TlsConfig;
+use crate::https_enforcement::TerminatedTls;
+use anyhow::Context;
+use axum::{Extension, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+/// Loads the PEM certificate chain and private key of the given [TlsConfig] into a rustls
+/// [ServerConfig].
+pub fn server_config(config: &TlsConfig) -> anyhow::Result<Arc<ServerConfig>> {
+    let cert_chain = CertificateDer::pem_file_iter(&config.cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("read certificate chain {}", config.cert_path.display()))?;
+    anyhow::ensure!(
+        !cert_chain.is_empty(),
+        "no certificates in {}",
+        config.cert_path.display()
+    );
+    let key = PrivateKeyDer::from_pem_file(&config.key_path)
+        .with_context(|| format!("read private key {}", config.key_path.display()))?;
+
+    let mut server_config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .context("configure TLS protocol versions")?
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)
+        .context("configure TLS certificate")?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(Arc::new(server_config))
+}
+
+/// Serves the given [Router] over TLS with the given [ServerConfig] on the connections of the
+/// given [TcpListener]. Requests get the [TerminatedTls] extension and the
+/// `ConnectInfo<SocketAddr>` of their peers.
+pub async fn serve(
+    tcp_listener: TcpListener,
+    server_config: Arc<ServerConfig>,
+    router: Router,
+) -> std::io::Result<()> {
+    axum_server::from_tcp_rustls(
+        tcp_listener.into_std()?,
+        RustlsConfig::from_config(server_config),
+    )
+    .serve(
+        router
+            .layer(Extension(TerminatedTls))
+            .into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{HttpsEnforcementConfig, HttpsEnforcementMode};
+    use crate::https_enforcement::enforce_https;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio_rustls::TlsConnector;
+    use tokio_rustls::rustls::pki_types::ServerName;
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+
+    #[test]
+    fn server_config_fails_if_files_are_missing_or_invalid() {
+        let missing = TlsConfig {
+            cert_path: "/missing/cert.pem".into(),
+            ..TlsConfig::test()
+        };
+        let swapped = TlsConfig {
+            cert_path: TlsConfig::test().key_path,
+            key_path: TlsConfig::test().cert_path,
+        };
+
+        assert!(server_config(&TlsConfig::test()).is_ok());
+        assert!(server_config(&missing).is_err());
+        assert!(server_config(&swapped).is_err());
+    }
+
+    #[tokio::test]
+    async fn serve_serves_secure_requests_over_tls() -> anyhow::Result<()> {
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = tcp_listener.local_addr()?;
+        let https_enforcement = HttpsEnforcementConfig {
+            mode: HttpsEnforcementMode::Reject,
+            trusted_proxies: vec![],
+            canonical_host: None,
+        };
+        let router = Router::new()
+            .route("/health", axum::routing::get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                https_enforcement,
+                enforce_https,
+            ));
+        let server_config = server_config(&TlsConfig::test())?;
+        tokio::spawn(async move { serve(tcp_listener, server_config, router).await });
+
+        let mut roots = RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(TlsConfig::test().cert_path)? {
+            roots.add(cert?)?;
+        }
+        let client_config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let tcp_stream = TcpStream::connect(addr).await?;
+        let mut tls_stream = TlsConnector::from(Arc::new(client_config))
+            .connect(ServerName::try_from("localhost")?, tcp_stream)
+            .await?;
+
+        tls_stream
+            .write_all(b"GET /health HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await?;
+        let mut response = String::new();
+        tls_stream.read_to_string(&mut response).await?;
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("ok"), "{response}");
+
+        Ok(())
+    }
+}